@@ -0,0 +1,53 @@
+//! Plays random moves on boards without a window and prints how each game ended
+//!
+//! `cargo run -p board_plugin --example headless`
+
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::*;
+use board_plugin::components::Coordinate;
+use board_plugin::events::{GameOverEvent, NewGameEvent, TileTriggerEvent};
+use board_plugin::resources::{Board, BoardOptions, Difficulty};
+use board_plugin::HeadlessBoardPlugin;
+use rand::seq::IteratorRandom;
+
+const GAMES: usize = 10;
+
+fn main() {
+    let difficulty = Difficulty::Beginner;
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, HeadlessBoardPlugin))
+        .insert_resource(BoardOptions {
+            map_size: difficulty.map_size(),
+            bomb_count: difficulty.bomb_count(),
+            ..Default::default()
+        });
+
+    let mut rng = rand::thread_rng();
+    let mut won = 0;
+    // Events can outlive a game, they are only dropped once the fixed timestep has run
+    let mut game_over_evr = ManualEventReader::<GameOverEvent>::default();
+    for game in 1..=GAMES {
+        let mut moves = 0;
+        let result = loop {
+            app.update();
+            let events = app.world.resource::<Events<GameOverEvent>>();
+            if let Some(result) = game_over_evr.read(events).last() {
+                break *result;
+            }
+            let board = app.world.resource::<Board>();
+            let coord: Option<Coordinate> = board.states.covered().choose(&mut rng);
+            if let Some(coord) = coord {
+                app.world.send_event(TileTriggerEvent(coord));
+                moves += 1;
+            }
+        };
+        if result == GameOverEvent::Won {
+            won += 1;
+        }
+        println!("game {game}: {result:?} after {moves} moves");
+        app.world.send_event(NewGameEvent);
+        // Let the new board replace the finished one
+        app.update();
+    }
+    println!("won {won} of {GAMES} games");
+}
@@ -0,0 +1,113 @@
+//! Plays many games with a bot to measure how well it does
+
+use crate::bot::{Action, Player};
+use crate::game::{Game, GameState};
+use crate::resources::BoardOptions;
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GameReport {
+    pub won: bool,
+    pub time: Duration,
+    pub moves: u32,
+    pub guesses: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HarnessReport {
+    pub player: String,
+    pub games: u32,
+    pub won: u32,
+    pub time: Duration,
+    pub guesses: u32,
+}
+
+impl HarnessReport {
+    pub fn win_rate(&self) -> f64 {
+        self.won as f64 / self.games.max(1) as f64
+    }
+
+    pub fn average_time(&self) -> Duration {
+        self.time / self.games.max(1)
+    }
+
+    pub fn average_guesses(&self) -> f64 {
+        self.guesses as f64 / self.games.max(1) as f64
+    }
+}
+
+impl Display for HarnessReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: won {}/{} ({:.1}%), {:.2?} per game, {:.2} guesses per game",
+            self.player,
+            self.won,
+            self.games,
+            self.win_rate() * 100.,
+            self.average_time(),
+            self.average_guesses()
+        )
+    }
+}
+
+/// Plays one game until it is won or lost. A player going in circles loses
+pub fn play_game(player: &mut dyn Player, options: &BoardOptions) -> GameReport {
+    let start = Instant::now();
+    let mut game = Game::new(options);
+    let max_moves = game.width() as u32 * game.height() as u32 * 4;
+    let mut report = GameReport {
+        won: false,
+        time: Duration::ZERO,
+        moves: 0,
+        guesses: 0,
+    };
+    while game.state() == GameState::Playing && report.moves < max_moves {
+        let decision = player.decide(&game.view());
+        report.moves += 1;
+        report.guesses += decision.guess as u32;
+        match decision.action {
+            Action::Reveal(coord) => {
+                game.reveal(coord);
+            }
+            Action::Flag(coord) => {
+                game.toggle_mark(coord);
+            }
+            Action::Chord(coord) => {
+                game.chord(coord);
+            }
+        }
+    }
+    report.won = game.state() == GameState::Won;
+    report.time = start.elapsed();
+    report
+}
+
+/// Plays `games` games on boards seeded from `seed` onwards, so that every player gets the same
+/// boards
+pub fn run(
+    player: &mut dyn Player,
+    options: &BoardOptions,
+    games: u32,
+    seed: u64,
+) -> HarnessReport {
+    let mut report = HarnessReport {
+        player: player.name().to_string(),
+        games,
+        won: 0,
+        time: Duration::ZERO,
+        guesses: 0,
+    };
+    for i in 0..games {
+        let options = BoardOptions {
+            seed: Some(seed.wrapping_add(i as u64)),
+            ..options.clone()
+        };
+        let game = play_game(player, &options);
+        report.won += game.won as u32;
+        report.time += game.time;
+        report.guesses += game.guesses;
+    }
+    report
+}
@@ -0,0 +1,12 @@
+mod player;
+pub use player::*;
+
+pub mod rules;
+pub use rules::RuleBot;
+
+pub mod probability;
+pub use probability::ProbabilityBot;
+
+pub mod harness;
+
+pub mod rating;
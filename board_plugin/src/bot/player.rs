@@ -0,0 +1,96 @@
+use crate::bot::{ProbabilityBot, RuleBot};
+use crate::components::Coordinate;
+use crate::game::{BoardView, TileView};
+use crate::resources::Mark;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    Reveal(Coordinate),
+    /// Toggles the mark of a covered tile
+    Flag(Coordinate),
+    /// Reveals the unflagged neighbors of a revealed number
+    Chord(Coordinate),
+}
+
+/// An action and whether the player had to guess to take it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Decision {
+    pub action: Action,
+    pub guess: bool,
+}
+
+impl Decision {
+    pub fn sure(action: Action) -> Self {
+        Self {
+            action,
+            guess: false,
+        }
+    }
+
+    pub fn guess(action: Action) -> Self {
+        Self {
+            action,
+            guess: true,
+        }
+    }
+}
+
+/// Plays the game from what can be seen of the board, one action at a time
+pub trait Player: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Called while the game is running, with at least one covered tile left
+    fn decide(&mut self, view: &BoardView) -> Decision;
+}
+
+/// Covered tiles which are not flagged
+pub fn is_unknown(tile: TileView) -> bool {
+    matches!(tile, TileView::Covered(mark) if mark != Some(Mark::Flag))
+}
+
+pub fn is_flag(tile: TileView) -> bool {
+    tile == TileView::Covered(Some(Mark::Flag))
+}
+
+/// The bots shipped with the game
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BotKind {
+    Rules,
+    Probability,
+}
+
+impl BotKind {
+    pub const ALL: [BotKind; 2] = [BotKind::Rules, BotKind::Probability];
+
+    /// Creates the bot, its guesses are drawn from `seed`
+    pub fn player(self, seed: u64) -> Box<dyn Player> {
+        match self {
+            BotKind::Rules => Box::new(RuleBot::new(seed)),
+            BotKind::Probability => Box::new(ProbabilityBot::new(seed)),
+        }
+    }
+}
+
+impl Display for BotKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BotKind::Rules => "rules",
+            BotKind::Probability => "probability",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for BotKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rules" => Ok(BotKind::Rules),
+            "probability" => Ok(BotKind::Probability),
+            _ => Err(format!("unknown bot {s:?}, expected rules or probability")),
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Mine probabilities of the unknown tiles, from every number and the number of mines left
+
+use crate::bot::rules::{self, Constraint};
+use crate::bot::{is_unknown, Action, Decision, Player};
+use crate::components::Coordinate;
+use crate::game::BoardView;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+/// Probabilities this close to 0 or 1 are certain
+pub(crate) const EPSILON: f64 = 1e-9;
+/// Largest group of linked tiles whose mine arrangements are enumerated
+const MAX_GROUP: usize = 64;
+/// Search steps allowed per group before giving up
+const MAX_STEPS: usize = 1_000_000;
+
+/// Mine arrangements of a group of linked tiles, by number of mines
+struct Arrangements {
+    /// Weight of the arrangements with `k` mines
+    counts: Vec<f64>,
+    /// Weight of the arrangements with `k` mines where each tile is a mine
+    tile_counts: Vec<Vec<f64>>,
+}
+
+/// Depth-first search of the arrangements satisfying the constraints of a group
+struct Search<'a> {
+    /// Constraints containing each tile
+    tile_constraints: Vec<Vec<usize>>,
+    needed: Vec<i32>,
+    mines: Vec<i32>,
+    unassigned: Vec<i32>,
+    assignment: Vec<bool>,
+    steps: usize,
+    result: &'a mut Arrangements,
+}
+
+impl Search<'_> {
+    /// Returns false when the search was too long
+    fn run(&mut self, tile: usize) -> bool {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return false;
+        }
+        if tile == self.assignment.len() {
+            let k = self.assignment.iter().filter(|m| **m).count();
+            self.result.counts[k] += 1.;
+            for (i, mine) in self.assignment.iter().enumerate() {
+                if *mine {
+                    self.result.tile_counts[k][i] += 1.;
+                }
+            }
+            return true;
+        }
+        for mine in [false, true] {
+            self.assignment[tile] = mine;
+            let mut valid = true;
+            for &c in &self.tile_constraints[tile] {
+                self.unassigned[c] -= 1;
+                self.mines[c] += mine as i32;
+                valid &= self.mines[c] <= self.needed[c]
+                    && self.mines[c] + self.unassigned[c] >= self.needed[c];
+            }
+            let finished = !valid || self.run(tile + 1);
+            for &c in &self.tile_constraints[tile] {
+                self.unassigned[c] += 1;
+                self.mines[c] -= mine as i32;
+            }
+            if !finished {
+                return false;
+            }
+        }
+        self.assignment[tile] = false;
+        true
+    }
+}
+
+fn arrangements(tiles: &[Coordinate], constraints: &[&Constraint]) -> Option<Arrangements> {
+    if tiles.len() > MAX_GROUP {
+        return None;
+    }
+    let position: HashMap<Coordinate, usize> =
+        tiles.iter().enumerate().map(|(i, t)| (*t, i)).collect();
+    let mut tile_constraints = vec![Vec::new(); tiles.len()];
+    for (c, constraint) in constraints.iter().enumerate() {
+        for tile in &constraint.tiles {
+            tile_constraints[position[tile]].push(c);
+        }
+    }
+    let mut result = Arrangements {
+        counts: vec![0.; tiles.len() + 1],
+        tile_counts: vec![vec![0.; tiles.len()]; tiles.len() + 1],
+    };
+    let mut search = Search {
+        tile_constraints,
+        needed: constraints.iter().map(|c| c.mines).collect(),
+        mines: vec![0; constraints.len()],
+        unassigned: constraints.iter().map(|c| c.tiles.len() as i32).collect(),
+        assignment: vec![false; tiles.len()],
+        steps: 0,
+        result: &mut result,
+    };
+    if !search.run(0) {
+        return None;
+    }
+    // Scaled down so that products over many groups stay finite
+    let total: f64 = result.counts.iter().sum();
+    if total == 0. {
+        return None;
+    }
+    result.counts.iter_mut().for_each(|c| *c /= total);
+    for counts in &mut result.tile_counts {
+        counts.iter_mut().for_each(|c| *c /= total);
+    }
+    Some(result)
+}
+
+/// Distribution of the number of mines over several groups
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    result
+}
+
+/// Splits the tiles next to numbers into groups which don't share any constraint
+fn groups(constraints: &[Constraint]) -> Vec<(Vec<Coordinate>, Vec<&Constraint>)> {
+    let mut group_of: HashMap<Coordinate, usize> = HashMap::new();
+    let mut groups: Vec<(Vec<Coordinate>, Vec<&Constraint>)> = Vec::new();
+    for constraint in constraints {
+        let mut linked: Vec<usize> = constraint
+            .tiles
+            .iter()
+            .filter_map(|t| group_of.get(t).copied())
+            .collect();
+        linked.sort();
+        linked.dedup();
+        let target = match linked.first() {
+            Some(first) => *first,
+            None => {
+                groups.push((Vec::new(), Vec::new()));
+                groups.len() - 1
+            }
+        };
+        // Merge the other linked groups into the first one
+        for other in linked.into_iter().skip(1) {
+            let (tiles, constraints) = std::mem::take(&mut groups[other]);
+            for tile in &tiles {
+                group_of.insert(*tile, target);
+            }
+            groups[target].0.extend(tiles);
+            groups[target].1.extend(constraints);
+        }
+        for tile in &constraint.tiles {
+            if group_of.insert(*tile, target).is_none() {
+                groups[target].0.push(*tile);
+            }
+        }
+        groups[target].1.push(constraint);
+    }
+    groups.retain(|(tiles, _)| !tiles.is_empty());
+    groups
+}
+
+/// Logarithms of the factorials up to `n`
+fn ln_factorials(n: usize) -> Vec<f64> {
+    let mut result = vec![0.; n + 1];
+    for i in 1..=n {
+        result[i] = result[i - 1] + (i as f64).ln();
+    }
+    result
+}
+
+/// The probability of each unknown tile being a mine, in board order. `None` if the board is
+/// too large to enumerate or the flags are inconsistent with the numbers
+pub fn mine_probabilities(view: &BoardView) -> Option<Vec<(Coordinate, f64)>> {
+    let mines_left = view.mines_left();
+    if mines_left < 0 {
+        return None;
+    }
+    let mines_left = mines_left as usize;
+    let constraints = rules::constraints(view);
+    let unknown: Vec<Coordinate> = view
+        .coordinates()
+        .filter(|c| is_unknown(view.tile(*c)))
+        .collect();
+
+    let groups = groups(&constraints);
+    let mut arrangements_by_group = Vec::new();
+    for (tiles, constraints) in &groups {
+        arrangements_by_group.push(arrangements(tiles, constraints)?);
+    }
+    let frontier: usize = groups.iter().map(|(tiles, _)| tiles.len()).sum();
+    let interior = unknown.len() - frontier;
+
+    // Weight of `m` mines next to numbers: the ways to place the other mines on the interior
+    let ln_factorials = ln_factorials(interior);
+    let ln_binomial =
+        |k: usize| ln_factorials[interior] - ln_factorials[k] - ln_factorials[interior - k];
+    let max_ln = (mines_left.saturating_sub(frontier)..=mines_left.min(interior))
+        .map(ln_binomial)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let weight = |m: usize| {
+        if m > mines_left || mines_left - m > interior {
+            0.
+        } else {
+            (ln_binomial(mines_left - m) - max_ln).exp()
+        }
+    };
+
+    let all = arrangements_by_group
+        .iter()
+        .fold(vec![1.], |acc, a| convolve(&acc, &a.counts));
+    let total: f64 = all.iter().enumerate().map(|(m, w)| w * weight(m)).sum();
+    if total <= 0. {
+        return None;
+    }
+
+    let mut probabilities: HashMap<Coordinate, f64> = HashMap::new();
+    for (g, ((tiles, _), arrangements)) in groups.iter().zip(&arrangements_by_group).enumerate() {
+        let others = arrangements_by_group
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != g)
+            .fold(vec![1.], |acc, (_, a)| convolve(&acc, &a.counts));
+        for (i, tile) in tiles.iter().enumerate() {
+            let mut p = 0.;
+            for (k, counts) in arrangements.tile_counts.iter().enumerate() {
+                for (m, w) in others.iter().enumerate() {
+                    p += counts[i] * w * weight(k + m);
+                }
+            }
+            probabilities.insert(*tile, p / total);
+        }
+    }
+    let interior_probability = if interior == 0 {
+        0.
+    } else {
+        all.iter()
+            .enumerate()
+            .map(|(m, w)| w * weight(m) * mines_left.saturating_sub(m) as f64)
+            .sum::<f64>()
+            / total
+            / interior as f64
+    };
+    Some(
+        unknown
+            .into_iter()
+            .map(|c| (c, *probabilities.get(&c).unwrap_or(&interior_probability)))
+            .collect(),
+    )
+}
+
+/// Applies the rules, then reveals the tile least likely to be a mine
+pub struct ProbabilityBot {
+    rng: StdRng,
+}
+
+impl ProbabilityBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Player for ProbabilityBot {
+    fn name(&self) -> &str {
+        "probability"
+    }
+
+    fn decide(&mut self, view: &BoardView) -> Decision {
+        let constraints = rules::constraints(view);
+        if let Some(decision) =
+            rules::single_tile(&constraints).or_else(|| rules::subset(&constraints))
+        {
+            return decision;
+        }
+        let Some(probabilities) = mine_probabilities(view) else {
+            return rules::random_guess(view, &mut self.rng);
+        };
+        if let Some((coord, _)) = probabilities.iter().find(|(_, p)| *p < EPSILON) {
+            return Decision::sure(Action::Reveal(*coord));
+        }
+        if let Some((coord, _)) = probabilities.iter().find(|(_, p)| *p > 1. - EPSILON) {
+            return Decision::sure(Action::Flag(*coord));
+        }
+        let lowest = probabilities
+            .iter()
+            .map(|(_, p)| *p)
+            .fold(f64::INFINITY, f64::min);
+        let safest: Vec<Coordinate> = probabilities
+            .iter()
+            .filter(|(_, p)| *p < lowest + EPSILON)
+            .map(|(c, _)| *c)
+            .collect();
+        match safest.choose(&mut self.rng) {
+            Some(coord) => Decision::guess(Action::Reveal(*coord)),
+            None => rules::random_guess(view, &mut self.rng),
+        }
+    }
+}
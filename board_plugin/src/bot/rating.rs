@@ -0,0 +1,191 @@
+//! Rates how hard a board is by solving it with the bots' deductions
+
+use crate::bot::probability::{mine_probabilities, EPSILON};
+use crate::bot::rules::{self, Constraint};
+use crate::bot::{is_unknown, Action, Decision};
+use crate::components::Coordinate;
+use crate::game::{BoardView, Game, GameState};
+use crate::tile_map::TileMap;
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+
+/// Kinds of deduction, from the easiest to the hardest
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Deduction {
+    /// One number at a time
+    #[default]
+    SingleTile,
+    /// A number whose neighbors include those of another one
+    Subset,
+    /// Every number and the count of mines left at once
+    Global,
+}
+
+impl Deduction {
+    fn weight(self) -> f32 {
+        match self {
+            Deduction::SingleTile => 0.,
+            Deduction::Subset => 2.,
+            Deduction::Global => 4.,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoardRating {
+    /// Minimum number of clicks needed to reveal every safe tile
+    pub bbbv: u32,
+    /// Areas without any mine around, uncovered all at once
+    pub openings: u32,
+    /// Times no safe tile could be deduced, not counting the first click
+    pub forced_guesses: u32,
+    /// Hardest deduction needed
+    pub depth: Deduction,
+    /// Higher is harder: 6 per guess, 2 if subset deductions are needed or 4 for global ones,
+    /// plus up to 2 for the 3BV per safe tile
+    pub score: f32,
+}
+
+impl Display for BoardRating {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "3BV {}, {} openings, {} guesses, {:?} deductions, score {:.1}",
+            self.bbbv, self.openings, self.forced_guesses, self.depth, self.score
+        )
+    }
+}
+
+/// Safe tiles with no mine around
+fn is_empty(tile_map: &TileMap, coord: Coordinate) -> bool {
+    !tile_map.is_bomb_at(coord) && tile_map.bomb_count_at(coord) == 0
+}
+
+fn neighbors(tile_map: &TileMap, coord: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+    tile_map
+        .safe_square_at(coord)
+        .filter(|c| c.x < tile_map.width() && c.y < tile_map.height())
+}
+
+/// Counts the openings and the 3BV: one click per opening, and one per number that no opening
+/// reveals
+fn clicks(tile_map: &TileMap) -> (u32, u32) {
+    let (width, height) = (tile_map.width() as usize, tile_map.height() as usize);
+    let mut revealed = vec![false; width * height];
+    let index = |c: Coordinate| c.y as usize * width + c.x as usize;
+    let coordinates = (0..tile_map.height())
+        .flat_map(|y| (0..tile_map.width()).map(move |x| Coordinate { x, y }));
+    let mut openings = 0;
+    for coord in coordinates.clone() {
+        if revealed[index(coord)] || !is_empty(tile_map, coord) {
+            continue;
+        }
+        openings += 1;
+        revealed[index(coord)] = true;
+        let mut queue = VecDeque::from([coord]);
+        while let Some(coord) = queue.pop_front() {
+            for neighbor in neighbors(tile_map, coord) {
+                if !revealed[index(neighbor)] {
+                    revealed[index(neighbor)] = true;
+                    if is_empty(tile_map, neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+    }
+    let numbers = coordinates
+        .filter(|c| !revealed[index(*c)] && !tile_map.is_bomb_at(*c))
+        .count() as u32;
+    (openings, openings + numbers)
+}
+
+/// The safest tile to guess, knowing where the mines are so that the game goes on
+fn lucky_guess(view: &BoardView, tile_map: &TileMap) -> Option<Coordinate> {
+    let safe = |c: &Coordinate| !tile_map.is_bomb_at(*c);
+    match mine_probabilities(view) {
+        Some(probabilities) => probabilities
+            .into_iter()
+            .filter(|(c, _)| safe(c))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(c, _)| c),
+        None => view
+            .coordinates()
+            .filter(|c| is_unknown(view.tile(*c)))
+            .find(safe),
+    }
+}
+
+/// The next sure move and how hard it was to find
+fn deduce(view: &BoardView, constraints: &[Constraint]) -> Option<(Decision, Deduction)> {
+    if let Some(decision) = rules::single_tile(constraints) {
+        return Some((decision, Deduction::SingleTile));
+    }
+    if let Some(decision) = rules::subset(constraints) {
+        return Some((decision, Deduction::Subset));
+    }
+    let probabilities = mine_probabilities(view)?;
+    probabilities.iter().find_map(|(coord, p)| {
+        let action = if *p < EPSILON {
+            Action::Reveal(*coord)
+        } else if *p > 1. - EPSILON {
+            Action::Flag(*coord)
+        } else {
+            return None;
+        };
+        Some((Decision::sure(action), Deduction::Global))
+    })
+}
+
+/// Solves the board from a first click on an opening (or the safest number if there is none)
+pub fn rate(tile_map: &TileMap) -> BoardRating {
+    let (openings, bbbv) = clicks(tile_map);
+    let mut rating = BoardRating {
+        bbbv,
+        openings,
+        forced_guesses: 0,
+        depth: Deduction::SingleTile,
+        score: 0.,
+    };
+    let first = (0..tile_map.height())
+        .flat_map(|y| (0..tile_map.width()).map(move |x| Coordinate { x, y }))
+        .filter(|c| !tile_map.is_bomb_at(*c))
+        .min_by_key(|c| tile_map.bomb_count_at(*c));
+    let mut game = Game::from_tile_map(tile_map.clone(), false);
+    if let Some(first) = first {
+        game.reveal(first);
+    }
+    while game.state() == GameState::Playing {
+        let view = game.view();
+        let constraints = rules::constraints(&view);
+        let action = match deduce(&view, &constraints) {
+            Some((decision, deduction)) => {
+                rating.depth = rating.depth.max(deduction);
+                decision.action
+            }
+            None => {
+                rating.forced_guesses += 1;
+                match lucky_guess(&view, tile_map) {
+                    Some(coord) => Action::Reveal(coord),
+                    None => break,
+                }
+            }
+        };
+        match action {
+            Action::Reveal(coord) => {
+                game.reveal(coord);
+            }
+            Action::Flag(coord) => {
+                game.toggle_mark(coord);
+            }
+            Action::Chord(coord) => {
+                game.chord(coord);
+            }
+        }
+    }
+    let safe_tiles = tile_map.width() as u32 * tile_map.height() as u32 - tile_map.bomb_count();
+    rating.score = rating.forced_guesses as f32 * 6.
+        + rating.depth.weight()
+        + 2. * bbbv as f32 / safe_tiles.max(1) as f32;
+    rating
+}
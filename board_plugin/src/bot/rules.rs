@@ -0,0 +1,130 @@
+//! Deductions made from one or two numbers at a time
+
+use crate::bot::{is_flag, is_unknown, Action, Decision, Player};
+use crate::components::Coordinate;
+use crate::game::{BoardView, TileView};
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+/// A revealed number with unknown neighbors: `mines` of `tiles` are mines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    pub origin: Coordinate,
+    /// Sorted
+    pub tiles: Vec<Coordinate>,
+    pub mines: i32,
+}
+
+/// The constraints given by every revealed number, flags are trusted to be mines
+pub fn constraints(view: &BoardView) -> Vec<Constraint> {
+    view.coordinates()
+        .filter_map(|origin| {
+            let TileView::Revealed(count) = view.tile(origin) else {
+                return None;
+            };
+            let mut tiles: Vec<Coordinate> = view
+                .neighbors(origin)
+                .filter(|c| is_unknown(view.tile(*c)))
+                .collect();
+            if tiles.is_empty() {
+                return None;
+            }
+            tiles.sort();
+            let flags = view.neighbors(origin).filter(|c| is_flag(view.tile(*c)));
+            Some(Constraint {
+                origin,
+                tiles,
+                mines: count as i32 - flags.count() as i32,
+            })
+        })
+        .collect()
+}
+
+/// A number whose mines are all flagged, or whose unknown neighbors are all mines
+pub fn single_tile(constraints: &[Constraint]) -> Option<Decision> {
+    constraints.iter().find_map(|constraint| {
+        if constraint.mines == 0 {
+            Some(Decision::sure(Action::Chord(constraint.origin)))
+        } else if constraint.mines == constraint.tiles.len() as i32 {
+            Some(Decision::sure(Action::Flag(constraint.tiles[0])))
+        } else {
+            None
+        }
+    })
+}
+
+/// A number whose unknown neighbors include all those of another number: the difference in
+/// mines is spread over the remaining tiles
+pub fn subset(constraints: &[Constraint]) -> Option<Decision> {
+    let mut by_tile: HashMap<Coordinate, Vec<&Constraint>> = HashMap::new();
+    for constraint in constraints {
+        for tile in &constraint.tiles {
+            by_tile.entry(*tile).or_default().push(constraint);
+        }
+    }
+    for small in constraints {
+        // Any constraint containing `small` also contains its first tile
+        for large in &by_tile[&small.tiles[0]] {
+            if large.tiles.len() <= small.tiles.len()
+                || !small
+                    .tiles
+                    .iter()
+                    .all(|t| large.tiles.binary_search(t).is_ok())
+            {
+                continue;
+            }
+            let rest: Vec<Coordinate> = large
+                .tiles
+                .iter()
+                .filter(|t| small.tiles.binary_search(t).is_err())
+                .copied()
+                .collect();
+            let mines = large.mines - small.mines;
+            if mines == 0 {
+                return Some(Decision::sure(Action::Reveal(rest[0])));
+            }
+            if mines == rest.len() as i32 {
+                return Some(Decision::sure(Action::Flag(rest[0])));
+            }
+        }
+    }
+    None
+}
+
+/// Reveals a random unknown tile
+pub fn random_guess(view: &BoardView, rng: &mut StdRng) -> Decision {
+    let coord = view
+        .coordinates()
+        .filter(|c| is_unknown(view.tile(*c)))
+        .choose(rng)
+        .unwrap_or_default();
+    Decision::guess(Action::Reveal(coord))
+}
+
+/// Applies the single tile and subset rules, and guesses at random when they don't help
+pub struct RuleBot {
+    rng: StdRng,
+}
+
+impl RuleBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Player for RuleBot {
+    fn name(&self) -> &str {
+        "rules"
+    }
+
+    fn decide(&mut self, view: &BoardView) -> Decision {
+        let constraints = constraints(view);
+        single_tile(&constraints)
+            .or_else(|| subset(&constraints))
+            .unwrap_or_else(|| random_guess(view, &mut self.rng))
+    }
+}
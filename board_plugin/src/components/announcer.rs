@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+/// Accessibility live region whose label is read out by screen readers
+#[derive(Debug, Copy, Clone, Component)]
+pub struct Announcer;
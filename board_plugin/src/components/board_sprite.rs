@@ -0,0 +1,12 @@
+use bevy::prelude::Component;
+
+/// The `BoardAssets` material a sprite was created from, so it can be restyled
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Component)]
+pub enum BoardSprite {
+    Background,
+    Tile,
+    Cover,
+    Bomb,
+    Flag,
+    Question,
+}
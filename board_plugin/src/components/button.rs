@@ -4,4 +4,18 @@ use bevy::prelude::Component;
 pub struct RoundButton;
 
 #[derive(Component)]
-pub struct ExitWindow;
\ No newline at end of file
+pub struct ExitWindow;
+
+/// The menu panel of the exit window, hidden while the board is being viewed
+#[derive(Component)]
+pub struct ExitPanel;
+
+/// The button bringing the exit panel back once collapsed
+#[derive(Component)]
+pub struct ExitPanelToggle;
+
+#[derive(Component)]
+pub struct SettingsWindow;
+
+#[derive(Component)]
+pub struct PuzzleWindow;
@@ -1,4 +1,5 @@
 use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Sub};
 
@@ -11,7 +12,7 @@ use bevy_inspector_egui::prelude::*;
 #[cfg_attr(feature = "inspect", derive(Reflect, InspectorOptions))]
 #[cfg_attr(feature = "inspect", reflect(InspectorOptions))]
 #[cfg_attr(feature = "inspect", inspector(validate = |ability| ability.current_charges <= ability.max_charges))]
-#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Serialize, Deserialize)]
 pub struct Coordinate {
     #[cfg_attr(feature = "inspect", inspector(min = 0, max = 50))]
     pub x: u16,
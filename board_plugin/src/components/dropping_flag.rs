@@ -0,0 +1,6 @@
+use crate::components::Coordinate;
+use bevy::prelude::Component;
+
+/// A flag falling onto its tile, the chunk of the tile draws the flag once it landed
+#[derive(Debug, Copy, Clone, Component)]
+pub struct DroppingFlag(pub Coordinate);
@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+/// Text showing the shortcuts and status of the level editor
+#[derive(Debug, Copy, Clone, Component)]
+pub struct EditorHud;
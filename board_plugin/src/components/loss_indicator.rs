@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+/// Sprites displayed over the board after a loss, removed when the game is resumed
+#[derive(Component)]
+pub struct LossIndicator;
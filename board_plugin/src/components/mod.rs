@@ -1,12 +1,38 @@
 mod coordinate;
 mod bomb;
 mod bomb_neighbor;
-mod uncover;
 mod button;
+mod loss_indicator;
+mod tween;
+mod board_sprite;
+mod number_glyph;
+mod tile_cursor;
+mod announcer;
+mod world_coordinate;
+mod score_text;
+mod race_player;
+mod puzzle_hud;
+mod editor_hud;
+mod dropping_flag;
 
 pub use coordinate::Coordinate;
 pub use bomb::Bomb;
 pub use bomb_neighbor::BombNeighbor;
-pub use uncover::Uncover;
 pub use button::RoundButton;
-pub use button::ExitWindow;
\ No newline at end of file
+pub use button::ExitWindow;
+pub use button::ExitPanel;
+pub use button::ExitPanelToggle;
+pub use button::SettingsWindow;
+pub use button::PuzzleWindow;
+pub use loss_indicator::LossIndicator;
+pub use tween::{Tween, TweenEffect};
+pub use board_sprite::BoardSprite;
+pub use number_glyph::NumberGlyph;
+pub use tile_cursor::TileCursor;
+pub use announcer::Announcer;
+pub use world_coordinate::WorldCoordinate;
+pub use score_text::ScoreText;
+pub use race_player::{RaceHud, RacePlayer};
+pub use puzzle_hud::PuzzleHud;
+pub use editor_hud::EditorHud;
+pub use dropping_flag::DroppingFlag;
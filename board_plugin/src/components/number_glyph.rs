@@ -0,0 +1,7 @@
+use bevy::prelude::Component;
+
+/// A shape displayed alongside a neighbor count, so numbers can be told apart without color
+#[derive(Debug, Copy, Clone, Component)]
+pub struct NumberGlyph {
+    pub count: u8,
+}
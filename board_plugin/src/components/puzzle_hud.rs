@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+/// Text showing the name and goal of the puzzle being played
+#[derive(Debug, Copy, Clone, Component)]
+pub struct PuzzleHud;
@@ -0,0 +1,9 @@
+use bevy::prelude::Component;
+
+/// Board entity of a player of the race mode: 0 plays with the mouse, 1 with the keyboard
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct RacePlayer(pub usize);
+
+/// Text showing the progress of a player of the race or networked versus modes
+#[derive(Debug, Copy, Clone, Component)]
+pub struct RaceHud(pub usize);
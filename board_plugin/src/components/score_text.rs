@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+/// Text showing the score of the endless mode
+#[derive(Debug, Copy, Clone, Component)]
+pub struct ScoreText;
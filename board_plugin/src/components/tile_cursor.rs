@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+/// Highlight of the tile under the board cursor
+#[derive(Debug, Copy, Clone, Component)]
+pub struct TileCursor;
@@ -0,0 +1,42 @@
+use bevy::prelude::Component;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TweenEffect {
+    Fade,
+    Scale,
+    Flip,
+    Drop,
+    Explosion,
+}
+
+/// A short animation played on a sprite, the entity is despawned once it ends if `despawn` is set
+#[derive(Debug, Clone, Component)]
+pub struct Tween {
+    pub effect: TweenEffect,
+    pub delay: f32,
+    pub duration: f32,
+    pub elapsed: f32,
+    pub despawn: bool,
+}
+
+impl Tween {
+    pub fn new(effect: TweenEffect, duration: f32) -> Self {
+        Self {
+            effect,
+            delay: 0.,
+            duration,
+            elapsed: 0.,
+            despawn: false,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn despawning(mut self) -> Self {
+        self.despawn = true;
+        self
+    }
+}
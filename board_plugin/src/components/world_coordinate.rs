@@ -0,0 +1,54 @@
+use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::ops::Add;
+
+/// Position of a tile on a board without bounds, see `endless`
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    Hash,
+    Component,
+    Serialize,
+    Deserialize,
+)]
+pub struct WorldCoordinate {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl WorldCoordinate {
+    /// Chunk containing the tile, chunks being `CHUNK_SIZE` tiles wide
+    pub fn chunk(self) -> (i32, i32) {
+        let size = crate::board::CHUNK_SIZE as i32;
+        (self.x.div_euclid(size), self.y.div_euclid(size))
+    }
+
+    /// Position of the tile in its chunk, row by row
+    pub fn chunk_index(self) -> usize {
+        let size = crate::board::CHUNK_SIZE as i32;
+        (self.y.rem_euclid(size) * size + self.x.rem_euclid(size)) as usize
+    }
+}
+
+impl Add<(i8, i8)> for WorldCoordinate {
+    type Output = Self;
+    fn add(self, (x, y): (i8, i8)) -> Self {
+        Self {
+            x: self.x + x as i32,
+            y: self.y + y as i32,
+        }
+    }
+}
+
+impl Display for WorldCoordinate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
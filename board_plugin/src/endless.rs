@@ -0,0 +1,159 @@
+//! The rules of the endless mode, on a board without bounds generated as it is explored
+
+use crate::bit_set::BitSet;
+use crate::board::{Mark, CHUNK_SIZE};
+use crate::chunked_tile_map::ChunkedTileMap;
+use crate::components::WorldCoordinate;
+use crate::game::{flood_reveal, FloodBoard, TileView};
+use bevy::utils::HashMap;
+
+/// Most tiles uncovered by a single click, the rest of a larger opening stays covered
+pub const MAX_OPENING: usize = 1 << 16;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EndlessState {
+    Playing,
+    /// The mine that was revealed
+    Lost(WorldCoordinate),
+}
+
+/// Revealed and flagged tiles of a chunk
+#[derive(Debug, Clone)]
+struct ChunkStates {
+    revealed: BitSet,
+    flags: BitSet,
+}
+
+impl Default for ChunkStates {
+    fn default() -> Self {
+        let area = CHUNK_SIZE as usize * CHUNK_SIZE as usize;
+        Self {
+            revealed: BitSet::new(area),
+            flags: BitSet::new(area),
+        }
+    }
+}
+
+/// An endless game: there is no winning, the score is the number of safe tiles revealed before
+/// hitting a mine
+#[derive(Debug, Clone)]
+pub struct EndlessGame {
+    tile_map: ChunkedTileMap,
+    states: HashMap<(i32, i32), ChunkStates>,
+    cleared: u64,
+    state: EndlessState,
+}
+
+impl EndlessGame {
+    /// The same seed and density always give the same world
+    pub fn new(seed: u64, bombs_per_chunk: u32) -> Self {
+        Self {
+            tile_map: ChunkedTileMap::new(seed, bombs_per_chunk),
+            states: HashMap::default(),
+            cleared: 0,
+            state: EndlessState::Playing,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.tile_map.seed()
+    }
+
+    pub fn state(&self) -> EndlessState {
+        self.state
+    }
+
+    /// Safe tiles revealed so far
+    pub fn cleared(&self) -> u64 {
+        self.cleared
+    }
+
+    pub fn is_covered(&self, coord: WorldCoordinate) -> bool {
+        self.states
+            .get(&coord.chunk())
+            .is_none_or(|s| !s.revealed.contains(coord.chunk_index()))
+    }
+
+    pub fn is_flagged(&self, coord: WorldCoordinate) -> bool {
+        self.states
+            .get(&coord.chunk())
+            .is_some_and(|s| s.flags.contains(coord.chunk_index()))
+    }
+
+    pub fn is_bomb_at(&mut self, coord: WorldCoordinate) -> bool {
+        self.tile_map.is_bomb_at(coord)
+    }
+
+    pub(crate) fn tile(&mut self, coord: WorldCoordinate) -> crate::resources::tile::Tile {
+        self.tile_map.tile(coord)
+    }
+
+    /// What a player can see of a tile
+    pub fn view(&mut self, coord: WorldCoordinate) -> TileView {
+        if self.is_covered(coord) {
+            TileView::Covered(self.is_flagged(coord).then_some(Mark::Flag))
+        } else if self.tile_map.is_bomb_at(coord) {
+            TileView::Mine
+        } else {
+            TileView::Revealed(self.tile_map.bomb_count_at(coord))
+        }
+    }
+
+    /// Reveals a covered, unflagged tile and the empty area around it in a single pass,
+    /// returning the revealed tiles starting with the given one
+    pub fn reveal(&mut self, coord: WorldCoordinate) -> Vec<WorldCoordinate> {
+        if self.state != EndlessState::Playing || self.is_flagged(coord) {
+            return Vec::new();
+        }
+        let revealed: Vec<WorldCoordinate> = flood_reveal(self, coord)
+            .into_iter()
+            .map(|(coord, ())| coord)
+            .collect();
+        // Only the clicked tile can be a mine, the others are next to an empty tile
+        if revealed
+            .first()
+            .is_some_and(|c| self.tile_map.is_bomb_at(*c))
+        {
+            self.state = EndlessState::Lost(coord);
+        } else {
+            self.cleared += revealed.len() as u64;
+        }
+        revealed
+    }
+
+    /// Flags or unflags a covered tile, returning whether it is flagged
+    pub fn toggle_flag(&mut self, coord: WorldCoordinate) -> Option<bool> {
+        if self.state != EndlessState::Playing || !self.is_covered(coord) {
+            return None;
+        }
+        let flags = &mut self.states.entry(coord.chunk()).or_default().flags;
+        let index = coord.chunk_index();
+        if flags.insert(index) {
+            Some(true)
+        } else {
+            flags.remove(index);
+            Some(false)
+        }
+    }
+}
+
+impl FloodBoard for EndlessGame {
+    type Coord = WorldCoordinate;
+    type Uncovered = ();
+    const MAX_OPENING: usize = MAX_OPENING;
+
+    fn uncover(&mut self, coord: WorldCoordinate) -> Option<()> {
+        let states = self.states.entry(coord.chunk()).or_default();
+        states.revealed.insert(coord.chunk_index()).then_some(())
+    }
+
+    fn is_empty(&mut self, coord: WorldCoordinate) -> bool {
+        !self.tile_map.is_bomb_at(coord) && self.tile_map.bomb_count_at(coord) == 0
+    }
+
+    fn closed_neighbors(&mut self, coord: WorldCoordinate) -> Vec<WorldCoordinate> {
+        ChunkedTileMap::safe_square_at(coord)
+            .filter(|c| self.is_covered(*c) && !self.is_flagged(*c))
+            .collect()
+    }
+}
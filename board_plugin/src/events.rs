@@ -1,9 +1,78 @@
 use bevy::ecs::event::Event;
 
-use crate::components::Coordinate;
+use crate::components::{Coordinate, WorldCoordinate};
+use crate::ReplayAction;
 
 #[derive(Debug, Copy, Clone, Event)]
 pub struct TileTriggerEvent(pub Coordinate);
 
 #[derive(Debug, Copy, Clone, Event)]
-pub struct TileMarkEvent(pub Coordinate);
\ No newline at end of file
+pub struct TileMarkEvent(pub Coordinate);
+
+#[derive(Debug, Copy, Clone, Event)]
+pub enum HistoryEvent {
+    Undo,
+    Redo,
+}
+
+/// A move applied to the board, sent in the order the moves are applied so that they are
+/// recorded and broadcast that way
+#[derive(Debug, Copy, Clone, Event)]
+pub struct MovePlayedEvent(pub ReplayAction);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sound {
+    Reveal,
+    Opening,
+    Flag,
+    Unflag,
+    Chord,
+    Explosion,
+    Win,
+    ButtonHover,
+    ButtonPress,
+}
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct SoundEvent(pub Sound);
+
+/// Text read out by screen readers
+#[derive(Debug, Clone, Event)]
+pub struct AnnounceEvent(pub String);
+
+/// Replaces the current board with a new one built from `BoardOptions`
+#[derive(Debug, Copy, Clone, Event)]
+pub struct NewGameEvent;
+
+/// Sent when the last safe tile or a mine is uncovered
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Event)]
+pub enum GameOverEvent {
+    Won,
+    Lost(Coordinate),
+}
+
+/// Reveals a tile of the endless board
+#[derive(Debug, Copy, Clone, Event)]
+pub struct EndlessTriggerEvent(pub WorldCoordinate);
+
+/// Flags or unflags a tile of the endless board
+#[derive(Debug, Copy, Clone, Event)]
+pub struct EndlessMarkEvent(pub WorldCoordinate);
+
+/// Reveals a tile of a board of the race mode
+#[derive(Debug, Copy, Clone, Event)]
+pub struct RaceTriggerEvent {
+    pub player: usize,
+    pub coord: Coordinate,
+}
+
+/// Cycles the mark of a tile of a board of the race mode
+#[derive(Debug, Copy, Clone, Event)]
+pub struct RaceMarkEvent {
+    pub player: usize,
+    pub coord: Coordinate,
+}
+
+/// Replaces the current board with the daily one
+#[derive(Debug, Copy, Clone, Event)]
+pub struct DailyEvent;
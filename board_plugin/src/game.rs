@@ -0,0 +1,326 @@
+//! The rules of the game without any rendering, for frontends that don't use Bevy's ECS
+
+use crate::board::Mark;
+use crate::bot::rating::{self, BoardRating};
+use crate::components::Coordinate;
+use crate::tile_map::TileMap;
+use crate::tile_states::TileStates;
+use crate::{BoardOptions, Layout};
+use std::collections::VecDeque;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameState {
+    Playing,
+    Won,
+    /// The mine that was revealed
+    Lost(Coordinate),
+}
+
+/// What a player can see of a tile
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TileView {
+    Covered(Option<Mark>),
+    /// The number of adjacent mines
+    Revealed(u8),
+    Mine,
+}
+
+/// Everything a player can see of a board, see `bot::Player`
+#[derive(Debug, Clone)]
+pub struct BoardView {
+    pub width: u16,
+    pub height: u16,
+    pub bomb_count: u32,
+    /// Row by row, starting from the bottom
+    pub tiles: Vec<TileView>,
+}
+
+impl BoardView {
+    pub fn tile(&self, coord: Coordinate) -> TileView {
+        self.tiles[coord.y as usize * self.width as usize + coord.x as usize]
+    }
+
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coordinate { x, y }))
+    }
+
+    pub fn neighbors(&self, coord: Coordinate) -> impl Iterator<Item = Coordinate> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|d| *d != (0, 0))
+            .map(move |(dx, dy)| (coord.x as i32 + dx, coord.y as i32 + dy))
+            .filter(move |(x, y)| (0..width).contains(x) && (0..height).contains(y))
+            .map(|(x, y)| Coordinate {
+                x: x as u16,
+                y: y as u16,
+            })
+    }
+
+    /// Mines minus flags
+    pub fn mines_left(&self) -> i32 {
+        let flags = self
+            .tiles
+            .iter()
+            .filter(|t| **t == TileView::Covered(Some(Mark::Flag)))
+            .count();
+        self.bomb_count as i32 - flags as i32
+    }
+}
+
+#[derive(Debug)]
+pub struct Game {
+    tile_map: TileMap,
+    states: TileStates,
+    question_marks: bool,
+    /// The first reveal is never a mine, see `safe_first_reveal`
+    safe_start: bool,
+    state: GameState,
+}
+
+impl Game {
+    pub fn new(options: &BoardOptions) -> Self {
+        let tile_map = TileMap::from_options(options);
+        Self {
+            states: TileStates::from_options(tile_map.width(), tile_map.height(), options),
+            safe_start: options.safe_start && options.layout.is_none(),
+            ..Self::from_tile_map(tile_map, options.question_marks)
+        }
+    }
+
+    pub(crate) fn from_tile_map(tile_map: TileMap, question_marks: bool) -> Self {
+        Self {
+            states: TileStates::new(tile_map.width(), tile_map.height()),
+            tile_map,
+            question_marks,
+            safe_start: false,
+            state: GameState::Playing,
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.tile_map.width()
+    }
+
+    pub fn height(&self) -> u16 {
+        self.tile_map.height()
+    }
+
+    pub fn bomb_count(&self) -> u32 {
+        self.tile_map.bomb_count()
+    }
+
+    /// Mines minus flags, can be negative when too many tiles are flagged
+    pub fn mines_left(&self) -> i32 {
+        self.bomb_count() as i32 - self.states.flag_count() as i32
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn is_covered(&self, coord: Coordinate) -> bool {
+        self.states.is_covered(coord)
+    }
+
+    pub fn tile(&self, coord: Coordinate) -> TileView {
+        if self.states.is_covered(coord) {
+            TileView::Covered(self.states.mark(coord))
+        } else if self.tile_map.is_bomb_at(coord) {
+            TileView::Mine
+        } else {
+            TileView::Revealed(self.tile_map.bomb_count_at(coord))
+        }
+    }
+
+    pub fn view(&self) -> BoardView {
+        let width = self.width();
+        let tiles = (0..self.height())
+            .flat_map(|y| (0..width).map(move |x| Coordinate { x, y }))
+            .map(|coord| self.tile(coord))
+            .collect();
+        BoardView {
+            width,
+            height: self.height(),
+            bomb_count: self.bomb_count(),
+            tiles,
+        }
+    }
+
+    /// How hard the board is, whatever has been played so far
+    pub fn rating(&self) -> BoardRating {
+        rating::rate(&self.tile_map)
+    }
+
+    /// Mine positions, to play the same board elsewhere
+    pub fn layout(&self) -> Layout {
+        Layout::from_tile_map(&self.tile_map)
+    }
+
+    /// Number of tiles uncovered so far
+    pub fn revealed_count(&self) -> u32 {
+        self.width() as u32 * self.height() as u32 - self.states.covered_count()
+    }
+
+    /// Mines are only visible once the game is over
+    pub fn is_mine(&self, coord: Coordinate) -> Option<bool> {
+        (self.state != GameState::Playing).then(|| self.tile_map.is_bomb_at(coord))
+    }
+
+    /// Reveals a covered, unflagged tile and the empty area around it, returning the revealed
+    /// tiles
+    pub fn reveal(&mut self, coord: Coordinate) -> Vec<Coordinate> {
+        if self.state != GameState::Playing || self.states.mark(coord) == Some(Mark::Flag) {
+            return Vec::new();
+        }
+        if self.safe_start {
+            safe_first_reveal(&mut self.tile_map, &self.states, coord);
+        }
+        let mut tiles = FiniteTiles {
+            tile_map: &self.tile_map,
+            states: &mut self.states,
+        };
+        let revealed: Vec<Coordinate> = flood_reveal(&mut tiles, coord)
+            .into_iter()
+            .map(|(coord, _)| coord)
+            .collect();
+        if revealed
+            .first()
+            .is_some_and(|c| self.tile_map.is_bomb_at(*c))
+        {
+            self.state = GameState::Lost(coord);
+        } else if self.states.covered_count() == self.bomb_count() {
+            self.state = GameState::Won;
+        }
+        revealed
+    }
+
+    /// Cycles the mark of a covered tile, see `TileStates::cycle_mark`, returning the new mark
+    pub fn toggle_mark(&mut self, coord: Coordinate) -> Option<Option<Mark>> {
+        if self.state != GameState::Playing {
+            return None;
+        }
+        let (_, mark) = self.states.cycle_mark(coord, self.question_marks)?;
+        Some(mark)
+    }
+
+    /// Reveals the unflagged neighbors of a revealed number once as many neighbors are flagged
+    pub fn chord(&mut self, coord: Coordinate) -> Vec<Coordinate> {
+        let mut revealed = Vec::new();
+        for neighbor in chord_targets(&self.tile_map, &self.states, coord) {
+            revealed.extend(self.reveal(neighbor));
+        }
+        revealed
+    }
+}
+
+/// The covered, unflagged neighbors of a revealed number once as many of its neighbors are
+/// flagged, revealed together by a chord. Empty otherwise
+pub(crate) fn chord_targets(
+    tile_map: &TileMap,
+    states: &TileStates,
+    coord: Coordinate,
+) -> Vec<Coordinate> {
+    if states.is_covered(coord) || tile_map.is_bomb_at(coord) {
+        return Vec::new();
+    }
+    let count = tile_map.bomb_count_at(coord);
+    let covered: Vec<Coordinate> = tile_map
+        .safe_square_at(coord)
+        .filter(|c| states.is_covered(*c))
+        .collect();
+    let flags = covered
+        .iter()
+        .filter(|c| states.mark(**c) == Some(Mark::Flag))
+        .count();
+    if count == 0 || flags != count as usize {
+        return Vec::new();
+    }
+    covered
+        .into_iter()
+        .filter(|c| states.mark(*c) != Some(Mark::Flag))
+        .collect()
+}
+
+/// Moves away the mine under the first reveal of a board, while every tile is still covered.
+/// Returns where the mine went
+pub(crate) fn safe_first_reveal(
+    tile_map: &mut TileMap,
+    states: &TileStates,
+    coord: Coordinate,
+) -> Option<Coordinate> {
+    let tile_count = tile_map.width() as u32 * tile_map.height() as u32;
+    if states.covered_count() != tile_count {
+        return None;
+    }
+    tile_map.move_bomb(coord)
+}
+
+/// A board whose empty areas are opened by `flood_reveal`
+pub(crate) trait FloodBoard {
+    type Coord: Copy;
+    /// What is kept of an uncovered tile
+    type Uncovered;
+    /// Most tiles uncovered at once, the rest of a larger opening stays covered
+    const MAX_OPENING: usize = usize::MAX;
+
+    /// Uncovers a tile, `None` if it was already uncovered
+    fn uncover(&mut self, coord: Self::Coord) -> Option<Self::Uncovered>;
+    /// Whether an uncovered tile opens its neighbors: it is not a mine and has none around
+    fn is_empty(&mut self, coord: Self::Coord) -> bool;
+    /// The neighbors of a tile that are covered and not flagged
+    fn closed_neighbors(&mut self, coord: Self::Coord) -> Vec<Self::Coord>;
+}
+
+/// The tiles of a finite board, for `flood_reveal`
+pub(crate) struct FiniteTiles<'a> {
+    pub tile_map: &'a TileMap,
+    pub states: &'a mut TileStates,
+}
+
+impl FloodBoard for FiniteTiles<'_> {
+    type Coord = Coordinate;
+    /// The mark the tile had
+    type Uncovered = Option<Mark>;
+
+    fn uncover(&mut self, coord: Coordinate) -> Option<Option<Mark>> {
+        self.states.uncover(coord)
+    }
+
+    fn is_empty(&mut self, coord: Coordinate) -> bool {
+        !self.tile_map.is_bomb_at(coord) && self.tile_map.bomb_count_at(coord) == 0
+    }
+
+    fn closed_neighbors(&mut self, coord: Coordinate) -> Vec<Coordinate> {
+        self.tile_map
+            .safe_square_at(coord)
+            .filter(|c| self.states.is_covered(*c) && self.states.mark(*c) != Some(Mark::Flag))
+            .collect()
+    }
+}
+
+/// Uncovers a tile and, when it has no adjacent mine, the whole empty area around it in a single
+/// pass. Flagged tiles stay covered. Returns the uncovered tiles, starting with the given one, and
+/// what was kept of them
+pub(crate) fn flood_reveal<B: FloodBoard>(
+    board: &mut B,
+    origin: B::Coord,
+) -> Vec<(B::Coord, B::Uncovered)> {
+    let mut revealed = Vec::new();
+    let mut queue = VecDeque::from([origin]);
+    while let Some(coord) = queue.pop_front() {
+        if revealed.len() == B::MAX_OPENING {
+            break;
+        }
+        let Some(uncovered) = board.uncover(coord) else {
+            continue;
+        };
+        revealed.push((coord, uncovered));
+        // Only an empty tile opens its neighbors, none of which is a mine
+        if board.is_empty(coord) {
+            queue.extend(board.closed_neighbors(coord));
+        }
+    }
+    revealed
+}
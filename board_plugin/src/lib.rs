@@ -1,14 +1,18 @@
+pub mod bot;
 mod bounds;
 pub mod components;
-mod events;
+pub mod endless;
+pub mod events;
+pub mod game;
+pub mod net;
 pub mod resources;
 pub mod states;
 mod systems;
+pub mod theme;
 
 #[cfg(feature = "inspect")]
 use bevy::log;
 use bevy::prelude::*;
-use bevy::utils::HashMap;
 use bevy::window::{PrimaryWindow, Window};
 use bevy_round_ui::prelude::RoundUiPlugin;
 
@@ -19,46 +23,415 @@ use components::*;
 use events::*;
 use resources::*;
 use states::AppState;
+use theme::{Theme, ThemeLoader, Themes};
+use tile_states::TileStates;
 
+/// Plays the game in a window: renders the board, reads the mouse and keyboard and shows the
+/// menus
 pub struct BoardPlugin;
 
+/// Plays the game without window, input, rendering or menus, e.g. with `MinimalPlugins`.
+///
+/// A board is created from `BoardOptions` on startup and on every `NewGameEvent`. Moves are sent
+/// as `TileTriggerEvent`, `TileMarkEvent` and `HistoryEvent`, and the result is read from
+/// `GameOverEvent` and the `Board` resource.
+pub struct HeadlessBoardPlugin;
+
+impl Plugin for HeadlessBoardPlugin {
+    fn build(&self, app: &mut App) {
+        add_rules(app);
+        app.init_resource::<BoardAssets>()
+            .insert_state(AppState::InGame);
+    }
+}
+
+/// Events, resources and systems applying the rules to the board, shared by both plugins
+fn add_rules(app: &mut App) {
+    app.add_event::<TileTriggerEvent>()
+        .add_event::<TileMarkEvent>()
+        .add_event::<HistoryEvent>()
+        .add_event::<MovePlayedEvent>()
+        .add_event::<SoundEvent>()
+        .add_event::<NewGameEvent>()
+        .add_event::<GameOverEvent>()
+        .init_resource::<ExitWindowTitle>()
+        .init_resource::<NumberPalette>()
+        .add_systems(
+            OnEnter(AppState::InGame),
+            BoardPlugin::create_board
+                .run_if(single_board.and_then(not(resource_exists::<SpectatorClient>))),
+        )
+        .add_systems(
+            Update,
+            (
+                BoardPlugin::new_game.run_if(
+                    on_event::<NewGameEvent>()
+                        .and_then(resource_exists::<BoardAssets>)
+                        .and_then(single_board)
+                        .and_then(not(resource_exists::<NetClient>))
+                        .and_then(not(resource_exists::<SpectatorClient>)),
+                ),
+                systems::replay::start_recording
+                    .run_if(resource_exists::<ReplayRecorder>.and_then(resource_added::<Board>)),
+            )
+                .chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                systems::uncover::left_click_handler.run_if(not(resource_exists::<NetClient>)),
+                systems::uncover::uncover_tiles,
+                systems::mark::mark_tiles
+                    .after(systems::uncover::left_click_handler)
+                    .run_if(not(resource_exists::<NetClient>)),
+                systems::replay::record_moves
+                    .after(systems::history::undo_redo)
+                    .run_if(resource_exists::<ReplayRecorder>),
+                systems::replay::play_replay.run_if(resource_exists::<ReplayPlayback>),
+                systems::bot::drive_bot.run_if(resource_exists::<BotPlayer>),
+            )
+                .run_if(in_state(AppState::InGame).and_then(resource_exists::<Board>)),
+        )
+        .add_systems(
+            Update,
+            systems::history::undo_redo
+                .after(systems::mark::mark_tiles)
+                .run_if(resource_exists::<Board>.and_then(not(in_menu_screen))),
+        )
+        .add_systems(
+            OnExit(AppState::InGame),
+            (
+                systems::uncover::clear_tiles.run_if(resource_exists::<Board>),
+                systems::replay::stop_playback,
+            ),
+        );
+}
+
+/// Whether a single board is played, rather than the endless or race modes
+fn single_board(endless: Option<Res<EndlessOptions>>, race: Option<Res<RaceOptions>>) -> bool {
+    endless.is_none() && race.is_none()
+}
+
+/// Whether a screen of the menu hides the board: the settings or the puzzle selection
+fn in_menu_screen(state: Res<State<AppState>>) -> bool {
+    matches!(state.get(), AppState::Settings | AppState::Puzzles)
+}
+
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut App) {
+        add_rules(app);
         app.add_plugins(RoundUiPlugin)
-            .add_event::<TileTriggerEvent>()
-            .add_event::<TileMarkEvent>()
+            .add_event::<AnnounceEvent>()
             .init_resource::<ButtonStyle>()
-            .insert_state(AppState::InGame)
-            .add_systems(OnEnter(AppState::InGame), Self::create_board)
+            .init_resource::<AudioSettings>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<Settings>()
+            .init_asset::<Theme>()
+            .init_asset_loader::<ThemeLoader>()
+            .insert_state(AppState::Loading)
+            .add_systems(
+                Update,
+                (
+                    systems::settings::apply_settings.run_if(resource_changed::<Settings>),
+                    systems::theme::apply_theme.run_if(resource_exists::<Themes>),
+                    systems::theme::restyle_board.run_if(
+                        resource_exists::<Board>
+                            .or_else(resource_exists::<EndlessBoard>)
+                            .or_else(resource_exists::<Race>)
+                            .and_then(
+                                resource_changed::<BoardAssets>
+                                    .or_else(resource_changed::<NumberPalette>),
+                            ),
+                    ),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                systems::theme::finish_loading.run_if(in_state(AppState::Loading)),
+            )
+            .add_systems(Startup, systems::accessibility::setup_announcer)
+            .add_systems(
+                OnEnter(AppState::InGame),
+                systems::accessibility::focus_announcer,
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::input::handle_mouse_input.run_if(not(
+                        resource_exists::<ReplayPlayback>
+                            .or_else(resource_exists::<BotPlayer>)
+                            .or_else(resource_exists::<SpectatorClient>),
+                    )),
+                    systems::input::handle_cursor_input
+                        .run_if(not(resource_exists::<SpectatorClient>)),
+                )
+                    .run_if(in_state(AppState::InGame).and_then(resource_exists::<Board>)),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::accessibility::update_tile_cursor,
+                    systems::accessibility::announce_cursor,
+                )
+                    .run_if(
+                        in_state(AppState::InGame)
+                            .and_then(resource_exists::<Board>)
+                            .and_then(resource_changed::<Board>),
+                    ),
+            )
+            .add_systems(Update, systems::accessibility::apply_announcements)
+            .add_systems(
+                Update,
+                systems::input::handle_keyboard_input
+                    .before(systems::history::undo_redo)
+                    .run_if(
+                        resource_exists::<Board>
+                            .and_then(not(in_menu_screen))
+                            .and_then(not(in_state(AppState::Editor)))
+                            .and_then(not(resource_exists::<SpectatorClient>)),
+                    ),
+            )
+            .add_systems(
+                Update,
+                (systems::animation::animate, systems::audio::play_sounds),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::camera::reset_camera.run_if(
+                        resource_added::<Board>
+                            .or_else(resource_added::<EndlessBoard>)
+                            .or_else(resource_added::<Race>),
+                    ),
+                    systems::camera::pan_and_zoom
+                        .run_if(not(in_menu_screen).and_then(resource_exists::<Board>)),
+                    systems::chunks::build_tile_atlas.run_if(resource_exists::<BoardAssets>),
+                    systems::chunks::update_chunks
+                        .after(Self::new_game)
+                        .after(systems::uncover::uncover_tiles)
+                        .after(systems::mark::mark_tiles)
+                        .after(systems::mark::land_flags)
+                        .after(systems::history::undo_redo)
+                        .after(systems::race::mark_race_tiles)
+                        .after(systems::editor::edit_mines)
+                        .run_if(
+                            resource_exists::<TileAtlas>.and_then(
+                                resource_exists::<Board>.or_else(resource_exists::<Race>),
+                            ),
+                        ),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                systems::mark::land_flags
+                    .after(systems::animation::animate)
+                    .run_if(resource_exists::<Board>),
+            )
+            .add_event::<EndlessTriggerEvent>()
+            .add_event::<EndlessMarkEvent>()
+            .add_systems(
+                OnEnter(AppState::InGame),
+                systems::endless::create_endless_board.run_if(resource_exists::<EndlessOptions>),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::endless::new_endless_game.run_if(
+                        on_event::<NewGameEvent>().and_then(resource_exists::<EndlessOptions>),
+                    ),
+                    (
+                        (
+                            systems::endless::handle_endless_mouse_input,
+                            systems::endless::reveal_endless_tiles,
+                            systems::endless::mark_endless_tiles,
+                        )
+                            .chain()
+                            .run_if(in_state(AppState::InGame)),
+                        systems::camera::pan_and_zoom_endless.run_if(not(in_menu_screen)),
+                        systems::camera::follow_reveals,
+                        systems::endless::update_endless_chunks,
+                        systems::endless::update_score.run_if(resource_changed::<EndlessBoard>),
+                    )
+                        .chain()
+                        .run_if(resource_exists::<EndlessBoard>),
+                )
+                    .chain(),
+            )
+            .add_event::<RaceTriggerEvent>()
+            .add_event::<RaceMarkEvent>()
+            .add_systems(
+                OnEnter(AppState::InGame),
+                systems::race::create_race.run_if(resource_exists::<RaceOptions>),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::race::new_race.run_if(
+                        on_event::<NewGameEvent>().and_then(resource_exists::<RaceOptions>),
+                    ),
+                    (
+                        (
+                            systems::race::race_mouse_input,
+                            systems::race::race_keyboard_input,
+                            systems::race::reveal_race_tiles,
+                            systems::race::mark_race_tiles,
+                        )
+                            .chain()
+                            .run_if(in_state(AppState::InGame)),
+                        systems::race::update_race_cursors,
+                        systems::race::update_race_hud,
+                    )
+                        .chain()
+                        .run_if(resource_exists::<Race>),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                OnEnter(AppState::InGame),
+                systems::net::spawn_net_hud.run_if(resource_exists::<NetClient>),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::net::forward_moves.run_if(in_state(AppState::InGame)),
+                    systems::net::request_new_game.run_if(on_event::<NewGameEvent>()),
+                    systems::net::apply_server_messages,
+                    systems::net::update_net_hud,
+                )
+                    .chain()
+                    .after(systems::input::handle_mouse_input)
+                    .after(systems::input::handle_cursor_input)
+                    .before(systems::uncover::uncover_tiles)
+                    .run_if(resource_exists::<NetClient>),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::spectator::accept_spectators,
+                    systems::spectator::start_broadcast.run_if(resource_added::<Board>),
+                    systems::spectator::broadcast_moves.after(systems::history::undo_redo),
+                )
+                    .chain()
+                    .run_if(resource_exists::<SpectatorHost>),
+            )
+            .add_systems(
+                Update,
+                systems::spectator::watch_game
+                    .before(systems::uncover::left_click_handler)
+                    .run_if(
+                        resource_exists::<SpectatorClient>
+                            .and_then(not(in_state(AppState::Loading))),
+                    ),
+            )
+            .add_event::<DailyEvent>()
+            .add_systems(
+                Update,
+                (
+                    systems::daily::start_daily.run_if(
+                        on_event::<DailyEvent>()
+                            .and_then(resource_exists::<DailyHistory>)
+                            .and_then(resource_exists::<BoardAssets>),
+                    ),
+                    (
+                        systems::daily::count_daily_clicks,
+                        systems::daily::finish_daily,
+                    )
+                        .chain()
+                        .after(systems::uncover::uncover_tiles)
+                        .after(systems::mark::mark_tiles)
+                        .run_if(resource_exists::<DailyGame>.and_then(resource_exists::<Board>)),
+                ),
+            )
+            .add_systems(
+                OnEnter(AppState::Puzzles),
+                systems::puzzle::setup_puzzle_window.run_if(resource_exists::<PuzzlePacks>),
+            )
+            .add_systems(
+                Update,
+                systems::puzzle::handle_puzzle_actions
+                    .run_if(in_state(AppState::Puzzles).and_then(resource_exists::<PuzzlePacks>)),
+            )
             .add_systems(
                 Update,
                 (
-                    systems::input::handle_mouse_input,
-                    systems::uncover::left_click_handler,
-                    systems::uncover::uncover_tiles,
-                    systems::mark::mark_tiles,
+                    systems::puzzle::check_guesses
+                        .before(systems::uncover::left_click_handler)
+                        .run_if(in_state(AppState::InGame)),
+                    (
+                        systems::puzzle::solve_flag_puzzle.run_if(in_state(AppState::InGame)),
+                        systems::puzzle::show_puzzle_result,
+                    )
+                        .chain()
+                        .after(systems::uncover::uncover_tiles)
+                        .after(systems::mark::mark_tiles),
                 )
-                    .run_if(in_state(AppState::InGame)),
+                    .run_if(resource_exists::<PuzzleGame>.and_then(resource_exists::<Board>)),
+            )
+            .add_systems(
+                Update,
+                systems::puzzle::remove_puzzle_hud.run_if(resource_removed::<PuzzleGame>()),
+            )
+            .add_systems(
+                OnEnter(AppState::Editor),
+                systems::editor::enter_editor.run_if(resource_exists::<LevelEditor>),
+            )
+            .add_systems(OnExit(AppState::Editor), systems::editor::exit_editor)
+            .add_systems(
+                Update,
+                (
+                    systems::editor::edit_mines.run_if(resource_exists::<Board>),
+                    systems::editor::handle_editor_keys,
+                    systems::editor::update_editor_hud.run_if(resource_changed::<LevelEditor>),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Editor).and_then(resource_exists::<LevelEditor>)),
             )
-            .add_systems(OnExit(AppState::InGame), systems::uncover::clear_tiles)
             .add_systems(
                 OnEnter(AppState::Out),
-                systems::exit_handler::setup_exit_window,
+                (
+                    systems::exit_handler::setup_exit_window,
+                    systems::accessibility::announce_game_over,
+                    systems::replay::save_recording.run_if(resource_exists::<ReplayRecorder>),
+                ),
             )
             .add_systems(
                 Update,
                 (
-                    systems::exit_handler::handle_button_interactions,
                     systems::exit_handler::handle_button_actions,
+                    systems::exit_handler::reopen_exit_window,
                 )
                     .run_if(in_state(AppState::Out)),
+            )
+            .add_systems(
+                OnEnter(AppState::Settings),
+                systems::settings::setup_settings_window,
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::settings::handle_settings_actions,
+                    systems::settings::refresh_settings_labels.run_if(resource_changed::<Settings>),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Settings)),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::exit_handler::handle_button_interactions,
+                    systems::accessibility::navigate_menu
+                        .after(systems::settings::handle_settings_actions),
+                    systems::accessibility::handle_action_requests,
+                )
+                    .run_if(in_state(AppState::Out).or_else(in_menu_screen)),
             );
         #[cfg(feature = "inspect")]
         {
             app.register_type::<Coordinate>()
                 .register_type::<Bomb>()
-                .register_type::<BombNeighbor>()
-                .register_type::<Uncover>();
+                .register_type::<BombNeighbor>();
         }
         #[cfg(feature = "inspect")]
         log::info!("Loaded Board Plugin");
@@ -69,31 +442,106 @@ impl BoardPlugin {
     /// System to generate the complete board
     pub fn create_board(
         mut commands: Commands,
+        board: Option<Res<Board>>,
         board_options: Option<Res<BoardOptions>>,
         windows: Query<&Window, With<PrimaryWindow>>,
         board_assets: Res<BoardAssets>,
     ) {
-        let window = windows
-            .into_iter()
-            .next()
-            .expect("no primary window found!");
-        let options = match board_options {
-            None => BoardOptions::default(),
-            Some(o) => o.clone(),
-        };
+        if board.is_some() {
+            // A finished game was resumed by undoing its last move
+            return;
+        }
+        let board = Self::spawn_board(
+            &mut commands,
+            board_options.map(|o| o.clone()).unwrap_or_default(),
+            windows.get_single().ok(),
+            &board_assets,
+        );
+        commands.insert_resource(board);
+    }
+
+    /// Replaces the current board and starts playing, whatever the state
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_game(
+        mut commands: Commands,
+        mut new_game_evr: EventReader<NewGameEvent>,
+        board: Option<Res<Board>>,
+        board_options: Option<Res<BoardOptions>>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        board_assets: Res<BoardAssets>,
+        exit_window: Query<Entity, With<ExitWindow>>,
+        mut next_state: ResMut<NextState<AppState>>,
+    ) {
+        new_game_evr.clear();
+        commands.remove_resource::<DailyGame>();
+        commands.remove_resource::<PuzzleGame>();
+        Self::replace_board(
+            &mut commands,
+            board.as_deref(),
+            board_options.map(|o| o.clone()).unwrap_or_default(),
+            windows.get_single().ok(),
+            &board_assets,
+            &exit_window,
+            &mut next_state,
+        );
+    }
+
+    /// Despawns the current board and the menu, and starts playing a new board
+    pub(crate) fn replace_board(
+        commands: &mut Commands,
+        board: Option<&Board>,
+        options: BoardOptions,
+        window: Option<&Window>,
+        board_assets: &BoardAssets,
+        exit_window: &Query<Entity, With<ExitWindow>>,
+        next_state: &mut NextState<AppState>,
+    ) {
+        for entity in exit_window {
+            commands.entity(entity).despawn_recursive();
+        }
+        Self::respawn_board(commands, board, options, window, board_assets);
+        next_state.set(AppState::InGame);
+    }
+
+    /// Despawns the current board and spawns a new one in its place, keeping the state
+    pub(crate) fn respawn_board(
+        commands: &mut Commands,
+        board: Option<&Board>,
+        options: BoardOptions,
+        window: Option<&Window>,
+        board_assets: &BoardAssets,
+    ) {
+        if let Some(board) = board {
+            commands.entity(board.entity).despawn_recursive();
+            // Removed first so the new board counts as added
+            commands.remove_resource::<Board>();
+        }
+        let board = Self::spawn_board(commands, options, window, board_assets);
+        commands.insert_resource(board);
+    }
 
-        let mut tile_map = tile_map::TileMap::create(options.map_size.0, options.map_size.1);
-        tile_map.set_bombs(options.bomb_count);
+    /// Spawns the board entities, the tiles are spawned by chunks once in view. Without a
+    /// window, adaptive tiles get their maximum size
+    pub(crate) fn spawn_board(
+        commands: &mut Commands,
+        options: BoardOptions,
+        window: Option<&Window>,
+        board_assets: &BoardAssets,
+    ) -> Board {
+        let tile_map = tile_map::TileMap::from_options(&options);
         #[cfg(feature = "inspect")]
         log::info!("{}", tile_map.console_output());
 
         let tile_size = match options.tile_size {
             TileSize::Fixed(v) => v,
-            TileSize::Adaptive { min, max } => Self::adaptative_tile_size(
-                window,
-                (min, max),
-                (tile_map.width(), tile_map.height()),
-            ),
+            TileSize::Adaptive { min, max } => match window {
+                Some(window) => Self::adaptative_tile_size(
+                    window,
+                    (min, max),
+                    (tile_map.width(), tile_map.height()),
+                ),
+                None => max,
+            },
         };
         let board_size = Vec2::new(
             tile_map.width() as f32 * tile_size,
@@ -109,41 +557,49 @@ impl BoardPlugin {
             BoardPosition::Custom(p) => p,
         };
 
-        let mut covered_tiles =
-            HashMap::with_capacity((tile_map.width() * tile_map.height()).into());
         let board_entity = commands
             .spawn(SpatialBundle {
                 visibility: Visibility::Visible,
-                transform: Transform::from_translation(board_position.into()),
+                transform: Transform::from_translation(board_position),
                 ..Default::default()
             })
             .insert(Name::new("Board"))
             .with_children(|parent| {
                 parent
-                    .spawn(Self::board_base_bundle(&board_assets, board_size))
-                    .insert(Name::new("Background"));
-                Self::spawn_tiles(
-                    parent,
-                    &tile_map,
-                    tile_size,
-                    options.tile_padding,
-                    &board_assets,
-                    &mut covered_tiles,
-                );
+                    .spawn(Self::board_base_bundle(board_assets, board_size))
+                    .insert(Name::new("Background"))
+                    .insert(BoardSprite::Background);
+                parent
+                    .spawn(Self::tile_cursor_bundle(tile_size))
+                    .insert(Name::new("Tile Cursor"))
+                    .insert(TileCursor);
             })
             .id();
 
-        commands.insert_resource(Board {
+        Board {
+            states: TileStates::from_options(tile_map.width(), tile_map.height(), &options),
             tile_map,
             bounds: Bounds2 {
                 position: board_position.truncate(),
                 size: board_size,
             },
             tile_size,
-            covered_tiles,
-            marked_tiles: Vec::new(),
+            tile_padding: options.tile_padding,
+            chunks: Default::default(),
+            changed_chunks: Default::default(),
+            uncovering: Vec::new(),
+            covers_hidden: false,
+            question_marks: options.question_marks,
+            safe_start: options.safe_start && options.layout.is_none(),
+            moved_mine: None,
+            history: Default::default(),
+            reveal_origin: Default::default(),
+            practice_mode: options.practice_mode,
+            practice: false,
+            exploded: None,
+            cursor: Default::default(),
             entity: board_entity,
-        });
+        }
     }
 
     fn adaptative_tile_size(
@@ -169,71 +625,87 @@ impl BoardPlugin {
         }
     }
 
-    fn spawn_tiles(
-        parent: &mut ChildBuilder,
-        tile_map: &tile_map::TileMap,
+    /// Spawns a tile and its content centered on a position relative to its parent
+    pub(crate) fn spawn_tile(
+        commands: &mut Commands,
+        tile: tile::Tile,
+        position: Vec2,
         tile_size: f32,
         padding: f32,
         board_assets: &BoardAssets,
-        covered_tiles: &mut HashMap<Coordinate, Entity>,
-    ) {
+        palette: &NumberPalette,
+    ) -> Entity {
         let tile_size_nopadding = tile_size - padding;
         let tile_size_nopadding_vec2 = Some(Vec2::splat(tile_size_nopadding));
-        for (y, line) in tile_map.iter().enumerate() {
-            for (x, tile) in line.iter().enumerate() {
-                let coordinate = Coordinate {
-                    x: x as u16,
-                    y: y as u16,
-                };
-
-                let mut cmd = parent.spawn(Self::tile_bundle(
-                    board_assets,
-                    tile_size_nopadding_vec2,
-                    tile_size,
-                    coordinate,
-                ));
+        let mut cmd = commands.spawn(Self::tile_bundle(
+            board_assets,
+            tile_size_nopadding_vec2,
+            position,
+        ));
+        cmd.insert(BoardSprite::Tile);
+        match tile {
+            tile::Tile::Bomb => {
+                cmd.insert(Bomb).with_children(|parent| {
+                    parent
+                        .spawn(Self::bomb_bundle(board_assets, tile_size_nopadding_vec2))
+                        .insert(BoardSprite::Bomb);
+                });
+            }
+            tile::Tile::BombNeighbor(count) => {
+                let bomb_neighbor = BombNeighbor { count };
+                cmd.insert(bomb_neighbor).with_children(|parent| {
+                    parent.spawn(Self::bomb_count_text_bundle(
+                        board_assets,
+                        palette,
+                        count,
+                        tile_size_nopadding,
+                    ));
+                    parent
+                        .spawn(Self::number_glyph_bundle(
+                            board_assets,
+                            palette,
+                            count,
+                            tile_size_nopadding,
+                        ))
+                        .insert(NumberGlyph { count });
+                });
+            }
+            _ => (),
+        };
+        cmd.id()
+    }
 
-                cmd.insert(Name::new(format!("Tile ({}, {})", x, y)))
-                    .insert(coordinate)
-                    .with_children(|parent| {
-                        let entity = parent
-                            .spawn(Self::tile_cover_bundle(
-                                board_assets,
-                                tile_size_nopadding_vec2,
-                            ))
-                            .insert(Name::new("Tile Cover"))
-                            .id();
-                        covered_tiles.insert(coordinate, entity);
-                    });
+    /// Spawns the cover of a tile
+    pub(crate) fn spawn_cover(
+        commands: &mut Commands,
+        tile: Entity,
+        size: f32,
+        board_assets: &BoardAssets,
+    ) -> Entity {
+        commands
+            .spawn(Self::tile_cover_bundle(
+                board_assets,
+                Some(Vec2::splat(size)),
+            ))
+            .insert(Name::new("Tile Cover"))
+            .insert(BoardSprite::Cover)
+            .set_parent(tile)
+            .id()
+    }
 
-                match tile {
-                    crate::resources::tile::Tile::Bomb => {
-                        cmd.insert(Bomb).with_children(|parent| {
-                            parent.spawn(Self::bomb_bundle(board_assets, tile_size_nopadding_vec2));
-                        });
-                    }
-                    crate::resources::tile::Tile::BombNeighbor(count) => {
-                        let bomb_neighbor = BombNeighbor { count: *count };
-                        cmd.insert(bomb_neighbor).with_children(|parent| {
-                            parent.spawn(Self::bomb_count_text_bundle(
-                                board_assets,
-                                *count,
-                                tile_size_nopadding,
-                            ));
-                        });
-                    }
-                    _ => (),
-                };
-            }
+    fn tile_cursor_bundle(tile_size: f32) -> SpriteBundle {
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1., 1., 1., 0.3),
+                custom_size: Some(Vec2::splat(tile_size)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(tile_size / 2., tile_size / 2., 5.),
+            ..Default::default()
         }
     }
 
-    fn tile_bundle(
-        board_assets: &BoardAssets,
-        size: Option<Vec2>,
-        tile_size: f32,
-        coord: Coordinate,
-    ) -> SpriteBundle {
+    fn tile_bundle(board_assets: &BoardAssets, size: Option<Vec2>, position: Vec2) -> SpriteBundle {
         SpriteBundle {
             sprite: Sprite {
                 custom_size: size,
@@ -241,16 +713,15 @@ impl BoardPlugin {
                 ..Default::default()
             },
             texture: board_assets.tile_material.texture.clone(),
-            transform: Transform::from_xyz(
-                (coord.x as f32 * tile_size) + (tile_size / 2.),
-                (coord.y as f32 * tile_size) + (tile_size / 2.),
-                1.,
-            ),
+            transform: Transform::from_translation(position.extend(1.)),
             ..Default::default()
         }
     }
 
-    fn tile_cover_bundle(board_assets: &BoardAssets, size: Option<Vec2>) -> SpriteBundle {
+    pub(crate) fn tile_cover_bundle(
+        board_assets: &BoardAssets,
+        size: Option<Vec2>,
+    ) -> SpriteBundle {
         SpriteBundle {
             sprite: Sprite {
                 custom_size: size,
@@ -276,8 +747,13 @@ impl BoardPlugin {
         }
     }
 
-    fn bomb_count_text_bundle(board_assets: &BoardAssets, count: u8, size: f32) -> Text2dBundle {
-        let color = board_assets.bomb_counter_color(count);
+    fn bomb_count_text_bundle(
+        board_assets: &BoardAssets,
+        palette: &NumberPalette,
+        count: u8,
+        size: f32,
+    ) -> Text2dBundle {
+        let color = palette.color(board_assets, count);
         Text2dBundle {
             text: Text::from_sections(vec![TextSection {
                 value: count.to_string(),
@@ -292,4 +768,30 @@ impl BoardPlugin {
             ..Default::default()
         }
     }
+
+    /// Small shape in the corner of a number tile
+    fn number_glyph_bundle(
+        board_assets: &BoardAssets,
+        palette: &NumberPalette,
+        count: u8,
+        size: f32,
+    ) -> SpriteBundle {
+        let glyph_size = size * 0.35;
+        let offset = (size - glyph_size) / 2.;
+        SpriteBundle {
+            sprite: Sprite {
+                color: palette.color(board_assets, count),
+                custom_size: Some(Vec2::splat(glyph_size)),
+                ..Default::default()
+            },
+            texture: board_assets.number_glyph(count),
+            transform: Transform::from_xyz(offset, -offset, 1.),
+            visibility: if palette.glyphs {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            },
+            ..Default::default()
+        }
+    }
 }
@@ -0,0 +1,235 @@
+//! Messages exchanged between the game and `minesweeper_server`, one RON value per line over TCP.
+//!
+//! The server owns the rules: moves are sent as `ClientMessage::Action` and only applied to the
+//! boards once the server broadcasts them back, in the order it accepted them.
+
+use crate::board::Mark;
+use crate::components::Coordinate;
+use crate::{Layout, ReplayAction};
+use bevy::log;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, RecvError, Sender, TryRecvError};
+use std::sync::Mutex;
+use std::thread;
+use thiserror::Error;
+
+pub const DEFAULT_PORT: u16 = 4815;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetMode {
+    /// Every player races on their own copy of the same board
+    Versus,
+    /// All players share a single board
+    Coop,
+}
+
+impl Display for NetMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NetMode::Versus => "versus",
+            NetMode::Coop => "coop",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for NetMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "versus" => Ok(NetMode::Versus),
+            "coop" | "co-op" => Ok(NetMode::Coop),
+            _ => Err(format!("unknown mode {s:?}, expected versus or coop")),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetAction {
+    /// Reveals a covered tile, or chords a revealed number
+    Reveal(Coordinate),
+    /// Cycles the mark of a tile
+    Mark(Coordinate),
+}
+
+/// A game started by the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetGame {
+    /// Index of the receiving player
+    pub player: usize,
+    pub players: usize,
+    pub mode: NetMode,
+    pub layout: Layout,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientMessage {
+    Action(NetAction),
+    /// Asks for the next game once the current one is over
+    NewGame,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// Sent to every player when a game starts, including the first one
+    Start(NetGame),
+    /// A reveal accepted by the server. In versus it only applies to the board of its player
+    Revealed { player: usize, coord: Coordinate },
+    /// The new mark of a tile, see `Revealed`
+    Marked {
+        player: usize,
+        coord: Coordinate,
+        mark: Option<Mark>,
+    },
+    /// Percentage of the safe tiles a player revealed
+    Progress { player: usize, progress: u8 },
+    /// The player who won the versus game or cleared the co-op board, if any
+    GameOver { winner: Option<usize> },
+}
+
+/// What spectators receive from the game they watch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SpectatorMessage {
+    /// Sent when a spectator joins, followed by the moves already played, and whenever a new
+    /// board is started
+    Start {
+        layout: Layout,
+        question_marks: bool,
+    },
+    /// A move of the player, applied with the rules like the moves of a replay
+    Move(ReplayAction),
+}
+
+#[derive(Debug, Error)]
+pub enum NetError {
+    #[error("Connection error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse message: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("Could not serialize message: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("Unexpected message: {0}")]
+    Unexpected(String),
+    #[error("The connection was closed")]
+    Closed,
+}
+
+/// Writes a message on its own line
+pub fn send<T: Serialize>(writer: &mut impl Write, message: &T) -> Result<(), NetError> {
+    let mut line = ron::to_string(message)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the messages sent with `send`
+pub struct MessageReader<R> {
+    reader: BufReader<R>,
+    line: String,
+}
+
+impl<R: Read> MessageReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            line: String::new(),
+        }
+    }
+
+    /// Waits for the next message, `None` once the connection is closed
+    pub fn read<T: DeserializeOwned>(&mut self) -> Result<Option<T>, NetError> {
+        self.line.clear();
+        if self.reader.read_line(&mut self.line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(ron::from_str(self.line.trim_end())?))
+    }
+}
+
+/// A connection whose messages are read on their own thread, to be polled by systems
+pub(crate) struct Connection<T> {
+    stream: TcpStream,
+    messages: Mutex<Receiver<T>>,
+}
+
+impl<T: DeserializeOwned + Send + 'static> Connection<T> {
+    pub(crate) fn connect(address: impl ToSocketAddrs) -> Result<Self, NetError> {
+        let stream = TcpStream::connect(address)?;
+        let mut reader = MessageReader::new(stream.try_clone()?);
+        let (sender, receiver) = mpsc::channel();
+        // Stops with the connection, which is then seen as a disconnected channel
+        thread::spawn(move || loop {
+            match reader.read() {
+                Ok(Some(message)) => {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    log::error!("{e}");
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            stream,
+            messages: Mutex::new(receiver),
+        })
+    }
+
+    pub(crate) fn send(&self, message: &impl Serialize) -> Result<(), NetError> {
+        send(&mut &self.stream, message)
+    }
+
+    /// Waits for the next message
+    pub(crate) fn receive(&self) -> Result<T, NetError> {
+        let messages = self.messages.lock().expect("message receiver poisoned");
+        messages.recv().map_err(|RecvError| NetError::Closed)
+    }
+
+    /// The next message received, if any
+    pub(crate) fn try_receive(&self) -> Result<Option<T>, NetError> {
+        let messages = self.messages.lock().expect("message receiver poisoned");
+        match messages.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(NetError::Closed),
+        }
+    }
+}
+
+/// A connection whose messages are written on their own thread, so that a slow peer does not
+/// hold up the systems sending to it
+#[derive(Debug)]
+pub(crate) struct Writer<T> {
+    messages: Sender<T>,
+}
+
+impl<T: Serialize + Send + 'static> Writer<T> {
+    pub(crate) fn new(mut stream: TcpStream) -> Self {
+        let (sender, receiver) = mpsc::channel::<T>();
+        // Stops once the writer is dropped or the peer is gone, which then fails the next send
+        thread::spawn(move || {
+            for message in receiver {
+                if let Err(e) = send(&mut stream, &message) {
+                    log::info!("Connection closed: {e}");
+                    break;
+                }
+            }
+        });
+        Self { messages: sender }
+    }
+
+    /// Queues the message, fails once the connection is lost
+    pub(crate) fn send(&self, message: T) -> Result<(), NetError> {
+        self.messages.send(message).map_err(|_| NetError::Closed)
+    }
+}
@@ -0,0 +1,36 @@
+use bevy::ecs::system::Resource;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CoverEffect {
+    Fade,
+    Scale,
+    Flip,
+}
+
+/// Visual effects of the board. Animations are disabled when this resource is missing,
+/// which is the case in headless applications
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct AnimationOptions {
+    pub enabled: bool,
+    pub cover_effect: CoverEffect,
+    /// Duration of a cover removal, in seconds
+    pub cover_duration: f32,
+    /// Delay added per tile of distance from the clicked tile, in seconds
+    pub ripple_delay: f32,
+    pub flag_drop: bool,
+    pub explosion: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cover_effect: CoverEffect::Scale,
+            cover_duration: 0.2,
+            ripple_delay: 0.03,
+            flag_drop: true,
+            explosion: true,
+        }
+    }
+}
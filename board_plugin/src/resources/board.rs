@@ -1,83 +1,299 @@
 use crate::bounds::Bounds2;
 use crate::components::Coordinate;
+use crate::game::{
+    chord_targets, flood_reveal, safe_first_reveal, BoardView, FiniteTiles, TileView,
+};
+use crate::history::{History, Move};
 use crate::tile_map::TileMap;
-use bevy::log;
+use crate::tile_states::TileStates;
 use bevy::prelude::*;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Resource)]
+/// Width and height of the squares of tiles spawned together, see `Board::chunks`
+pub const CHUNK_SIZE: u16 = 16;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Mark {
+    Flag,
+    Question,
+}
+
+/// The board being played: a resource, or a component of each board entity in the race mode
+#[derive(Debug, Resource, Component)]
 pub struct Board {
     pub tile_map: TileMap,
     pub bounds: Bounds2,
     pub tile_size: f32,
-    pub covered_tiles: HashMap<Coordinate, Entity>,
-    pub marked_tiles: Vec<Coordinate>,
+    pub tile_padding: f32,
+    /// Covered and marked tiles
+    pub states: TileStates,
+    /// Chunks spawned because they are in view, by chunk coordinates. Each one is a single mesh
+    pub chunks: HashMap<(u16, u16), Entity>,
+    /// Spawned chunks whose tiles changed since they were drawn
+    pub changed_chunks: HashSet<(u16, u16)>,
+    /// Tiles clicked, uncovered with the area around them by `uncover_tiles`
+    pub uncovering: Vec<Coordinate>,
+    /// Set when leaving the game, the covers then only show the mistakes of a lost game
+    pub covers_hidden: bool,
+    pub question_marks: bool,
+    /// The first reveal is never a mine, only for generated boards. Cleared once used
+    pub safe_start: bool,
+    /// The mine moved away from the first reveal by the safe start, and where it went
+    pub moved_mine: Option<(Coordinate, Coordinate)>,
+    pub history: History,
+    /// The tile clicked to start the current reveal
+    pub reveal_origin: Coordinate,
+    /// Undo and redo are only available in practice mode
+    pub practice_mode: bool,
+    /// Set once a move was undone, the result of such a game doesn't count
+    pub practice: bool,
+    /// The mine that ended the game, if it was lost
+    pub exploded: Option<Coordinate>,
+    /// The tile selected with the keyboard or hovered with the mouse
+    pub cursor: Coordinate,
     pub entity: Entity,
 }
 
 impl Board {
-    pub fn mouse_position(&self, window: &Window, position: Vec2) -> Option<Coordinate> {
-        let new_position = Vec2::new(
-            position.x - window.width() / 2.,
-            window.height() / 2. - position.y,
-        );
-        if !self.bounds.in_bounds(new_position) {
+    /// Tile under a position in world coordinates
+    pub fn mouse_position(&self, position: Vec2) -> Option<Coordinate> {
+        if !self.bounds.in_bounds(position) {
             return None;
         }
-        let coordinate = new_position - self.bounds.position;
+        let coordinate = position - self.bounds.position;
         Some(Coordinate {
             x: (coordinate.x / self.tile_size) as u16,
             y: (coordinate.y / self.tile_size) as u16,
         })
     }
 
-    pub fn tile_to_uncover(&self, coord: &Coordinate) -> Option<&Entity> {
-        if self.marked_tiles.contains(coord) {
-            None
+    /// Whether a click reveals the tile: it is covered and not flagged
+    pub fn can_uncover(&self, coord: &Coordinate) -> bool {
+        self.states.is_covered(*coord) && self.mark_at(coord) != Some(Mark::Flag)
+    }
+
+    /// Tiles uncovered by clicking a tile: the tile itself if it can be uncovered, or the
+    /// neighbors revealed by a chord when it is a satisfied number
+    pub fn click_targets(&self, coord: &Coordinate) -> Vec<Coordinate> {
+        if self.can_uncover(coord) {
+            vec![*coord]
         } else {
-            self.covered_tiles.get(coord)
+            chord_targets(&self.tile_map, &self.states, *coord)
         }
     }
 
-    pub fn try_uncover_tile(&mut self, coord: &Coordinate) -> Option<Entity> {
-        if self.marked_tiles.contains(coord) {
-            self.unmark_tile(coord);
+    /// With a safe start, moves away the mine under the first reveal
+    pub fn make_first_reveal_safe(&mut self, coord: &Coordinate) {
+        if !self.safe_start || !self.can_uncover(coord) {
+            return;
         }
-        self.covered_tiles.remove(coord)
+        // Undoing the first reveal doesn't make the next one safe again
+        self.safe_start = false;
+        let Some(target) = safe_first_reveal(&mut self.tile_map, &self.states, *coord) else {
+            return;
+        };
+        self.moved_mine = Some((*coord, target));
+        self.mine_changed(*coord);
+        self.mine_changed(target);
     }
 
-    pub fn adjacent_covered_tiles(&self, coord: Coordinate) -> Vec<Entity> {
-        self.tile_map
+    /// Redraws a mine added or removed and the numbers around it
+    pub fn mine_changed(&mut self, coord: Coordinate) {
+        let tiles: Vec<Coordinate> = self
+            .tile_map
             .safe_square_at(coord)
-            .filter_map(|c| self.covered_tiles.get(&c))
-            .copied()
+            .chain([coord])
+            .filter(|c| self.states.index(*c).is_some())
+            .collect();
+        for tile in tiles {
+            self.tile_changed(tile);
+        }
+    }
+
+    /// Redraws the chunk of a tile, if spawned
+    pub fn tile_changed(&mut self, coord: Coordinate) {
+        let chunk = Self::chunk_of(coord);
+        if self.chunks.contains_key(&chunk) {
+            self.changed_chunks.insert(chunk);
+        }
+    }
+
+    /// Redraws every spawned chunk, e.g. when the covers are hidden or shown again
+    pub fn all_tiles_changed(&mut self) {
+        self.changed_chunks.extend(self.chunks.keys().copied());
+    }
+
+    /// Starts the reveal of a click, returning whether it chorded a number
+    pub fn click(&mut self, coord: &Coordinate) -> Option<bool> {
+        let targets = self.click_targets(coord);
+        if targets.is_empty() {
+            return None;
+        }
+        self.begin_reveal(*coord);
+        self.uncovering.extend(targets);
+        Some(!self.states.is_covered(*coord))
+    }
+
+    /// Whether the chunk of a tile is spawned, so that the tile is displayed
+    pub fn is_spawned(&self, coord: &Coordinate) -> bool {
+        self.chunks.contains_key(&Self::chunk_of(*coord))
+    }
+
+    /// Center of a tile relative to the board entity
+    pub fn tile_center(&self, coord: &Coordinate) -> Vec2 {
+        (Vec2::new(coord.x as f32, coord.y as f32) + 0.5) * self.tile_size
+    }
+
+    /// Starts recording the tiles uncovered by a click
+    pub fn begin_reveal(&mut self, origin: Coordinate) {
+        self.reveal_origin = origin;
+        self.history.push(Move::Reveal(Vec::new()));
+    }
+
+    /// Uncovers a tile and the empty area around it at once, recording them in the history.
+    /// Returns the uncovered tiles, starting with the given one
+    pub fn reveal(&mut self, coord: &Coordinate) -> Vec<Coordinate> {
+        let mut tiles = FiniteTiles {
+            tile_map: &self.tile_map,
+            states: &mut self.states,
+        };
+        flood_reveal(&mut tiles, *coord)
+            .into_iter()
+            .map(|(coord, mark)| {
+                self.history.record_reveal(coord, mark);
+                self.tile_changed(coord);
+                coord
+            })
             .collect()
     }
 
-    pub fn try_toggle_mark(&mut self, coord: &Coordinate) -> Option<(Entity, bool)> {
-        let entity = *self.covered_tiles.get(coord)?;
-        let mark = if self.marked_tiles.contains(coord) {
-            self.unmark_tile(coord)?;
-            false
-        } else {
-            self.marked_tiles.push(*coord);
-            true
+    /// Puts back the cover of an uncovered tile, used when undoing a reveal
+    pub fn restore_cover(&mut self, coord: Coordinate, mark: Option<Mark>) {
+        if self.states.index(coord).is_none() {
+            return;
+        }
+        self.states.cover(coord);
+        self.set_mark(&coord, mark);
+    }
+
+    pub fn mark_at(&self, coord: &Coordinate) -> Option<Mark> {
+        self.states.mark(*coord)
+    }
+
+    /// Cycles the mark of a covered tile, see `TileStates::cycle_mark`, returning the new mark
+    pub fn try_toggle_mark(&mut self, coord: &Coordinate) -> Option<Option<Mark>> {
+        let (from, to) = self.states.cycle_mark(*coord, self.question_marks)?;
+        self.history.push(Move::Mark {
+            coord: *coord,
+            from,
+            to,
+        });
+        self.tile_changed(*coord);
+        Some(to)
+    }
+
+    /// Overrides the mark of a tile without recording it in the history
+    pub fn set_mark(&mut self, coord: &Coordinate, mark: Option<Mark>) {
+        self.states.set_mark(*coord, mark);
+        self.tile_changed(*coord);
+    }
+
+    /// Moves the cursor by the given offset, staying inside the board
+    pub fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let x = (self.cursor.x as i32 + dx).clamp(0, self.tile_map.width() as i32 - 1);
+        let y = (self.cursor.y as i32 + dy).clamp(0, self.tile_map.height() as i32 - 1);
+        self.cursor = Coordinate {
+            x: x as u16,
+            y: y as u16,
         };
-        Some((entity, mark))
     }
 
-    fn unmark_tile(&mut self, coords: &Coordinate) -> Option<Coordinate> {
-        let pos = match self.marked_tiles.iter().position(|a| a == coords) {
-            None => {
-                log::error!("Failed to unmark tile at {}", coords);
-                return None;
+    /// Spoken position of a tile, rows are counted from the top of the board
+    pub fn tile_position(&self, coord: &Coordinate) -> String {
+        format!(
+            "row {} column {}",
+            self.tile_map.height() - coord.y,
+            coord.x + 1
+        )
+    }
+
+    /// Spoken description of a tile, e.g. "row 3 column 5, covered, flagged"
+    pub fn describe_tile(&self, coord: &Coordinate) -> String {
+        let state = if self.states.is_covered(*coord) {
+            match self.mark_at(coord) {
+                Some(Mark::Flag) => "covered, flagged".to_string(),
+                Some(Mark::Question) => "covered, question mark".to_string(),
+                None => "covered".to_string(),
+            }
+        } else if self.tile_map.is_bomb_at(*coord) {
+            "mine".to_string()
+        } else {
+            match self.tile_map.bomb_count_at(*coord) {
+                0 => "revealed, empty".to_string(),
+                count => format!("revealed, {count}"),
             }
-            Some(p) => p,
         };
-        Some(self.marked_tiles.remove(pos))
+        format!("{}, {}", self.tile_position(coord), state)
+    }
+
+    /// What a player can see of the board
+    pub fn view(&self) -> BoardView {
+        let (width, height) = (self.tile_map.width(), self.tile_map.height());
+        let tiles = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Coordinate { x, y }))
+            .map(|coord| {
+                if self.states.is_covered(coord) {
+                    TileView::Covered(self.mark_at(&coord))
+                } else if self.tile_map.is_bomb_at(coord) {
+                    TileView::Mine
+                } else {
+                    TileView::Revealed(self.tile_map.bomb_count_at(coord))
+                }
+            })
+            .collect();
+        BoardView {
+            width,
+            height,
+            bomb_count: self.tile_map.bomb_count(),
+            tiles,
+        }
+    }
+
+    /// Chunk containing a tile
+    pub fn chunk_of(coord: Coordinate) -> (u16, u16) {
+        (coord.x / CHUNK_SIZE, coord.y / CHUNK_SIZE)
+    }
+
+    /// Tiles of a chunk, the chunks on the top and right edges can be smaller
+    pub fn chunk_tiles(&self, (x, y): (u16, u16)) -> impl Iterator<Item = Coordinate> {
+        let (width, height) = (self.tile_map.width(), self.tile_map.height());
+        let xs = x * CHUNK_SIZE..((x + 1) * CHUNK_SIZE).min(width);
+        let ys = y * CHUNK_SIZE..((y + 1) * CHUNK_SIZE).min(height);
+        ys.flat_map(move |y| xs.clone().map(move |x| Coordinate { x, y }))
+    }
+
+    /// Chunks overlapping an area in world coordinates
+    pub fn chunks_in(&self, area: Rect) -> Vec<(u16, u16)> {
+        let chunk_size = CHUNK_SIZE as f32 * self.tile_size;
+        let columns = self.tile_map.width().div_ceil(CHUNK_SIZE);
+        let rows = self.tile_map.height().div_ceil(CHUNK_SIZE);
+        let min = ((area.min - self.bounds.position) / chunk_size).floor();
+        let max = ((area.max - self.bounds.position) / chunk_size).floor();
+        if max.x < 0. || max.y < 0. || min.x >= columns as f32 || min.y >= rows as f32 {
+            return Vec::new();
+        }
+        let (x0, y0) = (min.x.max(0.) as u16, min.y.max(0.) as u16);
+        let (x1, y1) = (
+            (max.x as u16).min(columns - 1),
+            (max.y as u16).min(rows - 1),
+        );
+        (y0..=y1)
+            .flat_map(|y| (x0..=x1).map(move |x| (x, y)))
+            .collect()
     }
 
     pub fn is_completed(&self) -> bool {
-        self.tile_map.bomb_count() as usize == self.covered_tiles.len()
+        self.tile_map.bomb_count() == self.states.covered_count()
     }
 }
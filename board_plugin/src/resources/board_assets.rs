@@ -24,7 +24,46 @@ pub struct BoardAssets {
     pub bomb_counter_font: Handle<Font>,
     pub bomb_counter_colors: Vec<Color>,
     pub flag_material: SpriteMaterial,
+    pub question_material: SpriteMaterial,
     pub bomb_material: SpriteMaterial,
+    pub exploded_bomb_material: SpriteMaterial,
+    pub wrong_flag_material: SpriteMaterial,
+    pub explosion_material: SpriteMaterial,
+    /// Shapes for the numbers 1 to 8, displayed when glyphs are enabled
+    pub number_glyphs: Vec<Handle<Image>>,
+}
+
+/// Untextured assets, used when no theme is loaded such as in headless applications
+impl Default for BoardAssets {
+    fn default() -> Self {
+        Self {
+            label: "Default".to_string(),
+            board_material: SpriteMaterial {
+                color: Color::WHITE,
+                ..Default::default()
+            },
+            tile_material: SpriteMaterial {
+                color: Color::GRAY,
+                ..Default::default()
+            },
+            covered_tile_material: SpriteMaterial {
+                color: Color::DARK_GRAY,
+                ..Default::default()
+            },
+            bomb_counter_font: Default::default(),
+            bomb_counter_colors: Self::default_colors(),
+            flag_material: Default::default(),
+            question_material: Default::default(),
+            bomb_material: Default::default(),
+            exploded_bomb_material: SpriteMaterial {
+                color: Color::RED,
+                ..Default::default()
+            },
+            wrong_flag_material: Default::default(),
+            explosion_material: Default::default(),
+            number_glyphs: Vec::new(),
+        }
+    }
 }
 
 impl BoardAssets {
@@ -35,6 +74,9 @@ impl BoardAssets {
             Color::YELLOW,
             Color::ORANGE,
             Color::PURPLE,
+            Color::CYAN,
+            Color::NAVY,
+            Color::BLACK,
         ]
     }
 
@@ -48,4 +90,13 @@ impl BoardAssets {
             },
         }
     }
+
+    pub fn number_glyph(&self, counter: u8) -> Handle<Image> {
+        let counter = counter.saturating_sub(1) as usize;
+        self.number_glyphs
+            .get(counter)
+            .or(self.number_glyphs.last())
+            .cloned()
+            .unwrap_or_default()
+    }
 }
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Resource)]
+pub struct BoardAudio {
+    pub reveal: Handle<AudioSource>,
+    pub opening: Handle<AudioSource>,
+    pub flag: Handle<AudioSource>,
+    pub unflag: Handle<AudioSource>,
+    pub chord: Handle<AudioSource>,
+    pub explosion: Handle<AudioSource>,
+    pub win: Handle<AudioSource>,
+    pub button_hover: Handle<AudioSource>,
+    pub button_press: Handle<AudioSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.,
+            sfx_volume: 0.8,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn sfx_volume(&self) -> f32 {
+        if self.muted {
+            0.
+        } else {
+            (self.master_volume * self.sfx_volume).clamp(0., 1.)
+        }
+    }
+}
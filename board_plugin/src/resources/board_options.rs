@@ -1,6 +1,9 @@
+use crate::{Layout, StartPosition};
 use bevy::ecs::system::Resource;
 use bevy::prelude::Vec3;
 use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Resource)]
 pub enum TileSize {
@@ -34,11 +37,22 @@ impl Default for BoardPosition {
 #[derive(Debug, Clone, Serialize, Deserialize, Resource)]
 pub struct BoardOptions {
     pub map_size: (u16, u16), // (width, height)
-    pub bomb_count: u16,
+    pub bomb_count: u32,
     pub position: BoardPosition,
     pub tile_size: TileSize,
     pub tile_padding: f32,
     pub safe_start: bool,
+    pub question_marks: bool,
+    pub practice_mode: bool,
+    /// Seed of the mine placement, random if unset
+    pub seed: Option<u64>,
+    /// Fixed mine positions, overriding the map size, bomb count and seed
+    pub layout: Option<Layout>,
+    /// Tiles revealed or flagged before the first move, e.g. for a puzzle. Only used with a
+    /// `layout`
+    pub start: Option<StartPosition>,
+    /// Difficulty the generated board has to be rated at, see `bot::rating`
+    pub band: Option<DifficultyBand>,
 }
 
 impl Default for BoardOptions {
@@ -50,6 +64,133 @@ impl Default for BoardOptions {
             tile_size: Default::default(),
             tile_padding: 0.,
             safe_start: false,
+            question_marks: false,
+            practice_mode: false,
+            seed: None,
+            layout: None,
+            start: None,
+            band: None,
+        }
+    }
+}
+
+/// Classic board presets
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// 9x9, 10 mines
+    Beginner,
+    /// 16x16, 40 mines
+    Intermediate,
+    /// 30x16, 99 mines
+    Expert,
+}
+
+impl Difficulty {
+    pub fn map_size(self) -> (u16, u16) {
+        match self {
+            Difficulty::Beginner => (9, 9),
+            Difficulty::Intermediate => (16, 16),
+            Difficulty::Expert => (30, 16),
+        }
+    }
+
+    pub fn bomb_count(self) -> u32 {
+        match self {
+            Difficulty::Beginner => 10,
+            Difficulty::Intermediate => 40,
+            Difficulty::Expert => 99,
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Expert => "expert",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "beginner" => Ok(Difficulty::Beginner),
+            "intermediate" => Ok(Difficulty::Intermediate),
+            "expert" => Ok(Difficulty::Expert),
+            _ => Err(format!(
+                "unknown difficulty {s:?}, expected beginner, intermediate or expert"
+            )),
+        }
+    }
+}
+
+/// Ranges of board difficulty scores, see `bot::rating::BoardRating::score`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DifficultyBand {
+    /// Solvable one number at a time, without guessing
+    Easy,
+    /// Solvable without guessing
+    Medium,
+    /// One or two guesses
+    Hard,
+    /// Three guesses or more
+    Extreme,
+}
+
+impl DifficultyBand {
+    pub const ALL: [DifficultyBand; 4] = [
+        DifficultyBand::Easy,
+        DifficultyBand::Medium,
+        DifficultyBand::Hard,
+        DifficultyBand::Extreme,
+    ];
+
+    /// Lower and upper bound of the score
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            DifficultyBand::Easy => (0., 2.),
+            DifficultyBand::Medium => (2., 6.),
+            DifficultyBand::Hard => (6., 18.),
+            DifficultyBand::Extreme => (18., f32::INFINITY),
+        }
+    }
+
+    /// How far a score is from the band, 0 inside it
+    pub fn distance(self, score: f32) -> f32 {
+        let (min, max) = self.range();
+        (min - score).max(score - max).max(0.)
+    }
+}
+
+impl Display for DifficultyBand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DifficultyBand::Easy => "easy",
+            DifficultyBand::Medium => "medium",
+            DifficultyBand::Hard => "hard",
+            DifficultyBand::Extreme => "extreme",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for DifficultyBand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(DifficultyBand::Easy),
+            "medium" => Ok(DifficultyBand::Medium),
+            "hard" => Ok(DifficultyBand::Hard),
+            "extreme" => Ok(DifficultyBand::Extreme),
+            _ => Err(format!(
+                "unknown band {s:?}, expected easy, medium, hard or extreme"
+            )),
         }
     }
 }
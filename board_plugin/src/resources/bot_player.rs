@@ -0,0 +1,20 @@
+use crate::bot::Player;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// An AI player making the moves instead of the mouse, one every `delay` so that it can be
+/// watched
+#[derive(Resource)]
+pub struct BotPlayer {
+    pub(crate) player: Box<dyn Player>,
+    pub(crate) timer: Timer,
+}
+
+impl BotPlayer {
+    pub fn new(player: Box<dyn Player>, delay: Duration) -> Self {
+        Self {
+            player,
+            timer: Timer::new(delay, TimerMode::Once),
+        }
+    }
+}
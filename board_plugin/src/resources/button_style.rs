@@ -22,6 +22,8 @@ pub struct ButtonStyle {
     pub default: Handle<RoundUiMaterial>,
     pub hover: Handle<RoundUiMaterial>,
     pub press: Handle<RoundUiMaterial>,
+    pub panel_background: Color,
+    pub panel_border: Color,
 }
 
 impl FromWorld for ButtonStyle {
@@ -60,6 +62,8 @@ impl FromWorld for ButtonStyle {
                 size: Vec2::new(width, height),
                 offset: RoundUiOffset::top(offset).into(),
             }),
+            panel_background: Color::hex("5cb3af").unwrap(),
+            panel_border: Color::WHITE,
         }
     }
 }
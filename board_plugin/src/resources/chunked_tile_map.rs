@@ -0,0 +1,92 @@
+use crate::bit_set::BitSet;
+use crate::board::CHUNK_SIZE;
+use crate::components::WorldCoordinate;
+use crate::resources::tile::Tile;
+use crate::tile_map::SQUARE_COORDINATES;
+use bevy::utils::HashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const CHUNK_AREA: usize = CHUNK_SIZE as usize * CHUNK_SIZE as usize;
+
+/// Mines of a board without bounds, generated by chunk from the world seed when first needed.
+/// The tiles around the origin are always safe so that the game starts with an opening
+#[derive(Debug, Clone)]
+pub struct ChunkedTileMap {
+    seed: u64,
+    bombs_per_chunk: u32,
+    chunks: HashMap<(i32, i32), BitSet>,
+}
+
+impl ChunkedTileMap {
+    pub fn new(seed: u64, bombs_per_chunk: u32) -> Self {
+        Self {
+            seed,
+            // The safe tiles around the origin are kept out so that the placement ends
+            bombs_per_chunk: bombs_per_chunk.min(CHUNK_AREA as u32 - 9),
+            chunks: HashMap::default(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn is_start_area(coord: WorldCoordinate) -> bool {
+        coord.x.abs() <= 1 && coord.y.abs() <= 1
+    }
+
+    /// Places the bombs of a chunk, a given seed always gives the same chunk
+    fn generate(&self, (x, y): (i32, i32)) -> BitSet {
+        let chunk_seed = self.seed ^ ((x as u32 as u64) << 32 | y as u32 as u64);
+        let mut rng = StdRng::seed_from_u64(chunk_seed);
+        let mut bombs = BitSet::new(CHUNK_AREA);
+        let mut remained_bombs = self.bombs_per_chunk;
+        while remained_bombs > 0 {
+            let (dx, dy) = (rng.gen_range(0..CHUNK_SIZE), rng.gen_range(0..CHUNK_SIZE));
+            let coord = WorldCoordinate {
+                x: x * CHUNK_SIZE as i32 + dx as i32,
+                y: y * CHUNK_SIZE as i32 + dy as i32,
+            };
+            if !Self::is_start_area(coord) && bombs.insert(coord.chunk_index()) {
+                remained_bombs -= 1;
+            }
+        }
+        bombs
+    }
+
+    pub fn safe_square_at(coord: WorldCoordinate) -> impl Iterator<Item = WorldCoordinate> {
+        SQUARE_COORDINATES
+            .iter()
+            .copied()
+            .map(move |tuple| coord + tuple)
+    }
+
+    pub fn is_bomb_at(&mut self, coord: WorldCoordinate) -> bool {
+        let chunk = coord.chunk();
+        if !self.chunks.contains_key(&chunk) {
+            let bombs = self.generate(chunk);
+            self.chunks.insert(chunk, bombs);
+        }
+        self.chunks[&chunk].contains(coord.chunk_index())
+    }
+
+    pub fn bomb_count_at(&mut self, coord: WorldCoordinate) -> u8 {
+        if self.is_bomb_at(coord) {
+            return 0;
+        }
+        Self::safe_square_at(coord)
+            .filter(|c| self.is_bomb_at(*c))
+            .count() as u8
+    }
+
+    pub fn tile(&mut self, coord: WorldCoordinate) -> Tile {
+        if self.is_bomb_at(coord) {
+            return Tile::Bomb;
+        }
+        match self.bomb_count_at(coord) {
+            0 => Tile::Empty,
+            count => Tile::BombNeighbor(count),
+        }
+    }
+}
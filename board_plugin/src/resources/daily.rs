@@ -0,0 +1,204 @@
+use crate::{BoardOptions, Difficulty};
+use bevy::log;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Everyone plays the daily board with the same configuration
+pub const DAILY_DIFFICULTY: Difficulty = Difficulty::Intermediate;
+
+/// Days shown by the streak calendar, as rows of a week
+const CALENDAR_DAYS: i64 = 28;
+
+/// A day in UTC, counted from 1970-01-01, so that everyone gets the same board at the same time
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Day(pub i64);
+
+impl Day {
+    pub fn today() -> Self {
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Self((elapsed.as_secs() / 86_400) as i64)
+    }
+
+    /// Seed of the board of the day, scrambled so that consecutive days look unrelated
+    pub fn seed(self) -> u64 {
+        // SplitMix64
+        let mut z = (self.0 as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Year, month and day of the month
+    pub fn civil(self) -> (i64, u32, u32) {
+        // Howard Hinnant's days to civil date conversion
+        let z = self.0 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+
+    /// The board of the day
+    pub fn board_options(self, options: BoardOptions) -> BoardOptions {
+        BoardOptions {
+            map_size: DAILY_DIFFICULTY.map_size(),
+            bomb_count: DAILY_DIFFICULTY.bomb_count(),
+            seed: Some(self.seed()),
+            safe_start: false,
+            practice_mode: false,
+            layout: None,
+            start: None,
+            band: None,
+            ..options
+        }
+    }
+}
+
+impl Display for Day {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.civil();
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+/// The official attempt of a day
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub won: bool,
+    pub time: Duration,
+    /// Minimum number of clicks needed to clear the board
+    pub bbbv: u32,
+    /// Reveals and marks
+    pub clicks: u32,
+}
+
+impl DailyResult {
+    /// 3BV per second
+    pub fn speed(&self) -> f32 {
+        self.bbbv as f32 / self.time.as_secs_f32().max(0.001)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DailyError {
+    #[error("No data directory on this platform")]
+    NoDataDir,
+    #[error("Could not write the daily results: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not serialize the daily results: {0}")]
+    Ron(#[from] ron::Error),
+}
+
+/// Official daily attempts, saved in the platform data directory. Offers the daily mode in the
+/// menu when inserted
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Resource)]
+#[serde(default)]
+pub struct DailyHistory {
+    pub results: BTreeMap<Day, DailyResult>,
+}
+
+impl DailyHistory {
+    /// `<data dir>/minesweeper/daily.ron`
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("minesweeper").join("daily.ron"))
+    }
+
+    /// Reads the saved results, starting over if there are none or they are invalid
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match ron::from_str(&text) {
+            Ok(history) => history,
+            Err(e) => {
+                log::warn!(
+                    "Ignoring invalid daily results in {}: {}",
+                    path.display(),
+                    e
+                );
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), DailyError> {
+        let path = Self::path().ok_or(DailyError::NoDataDir)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, Default::default())?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Days won in a row until the given day, or the day before if it isn't played yet
+    pub fn streak(&self, today: Day) -> u32 {
+        let won = |day: i64| self.results.get(&Day(day)).is_some_and(|r| r.won);
+        let last = if self.results.contains_key(&today) {
+            today.0
+        } else {
+            today.0 - 1
+        };
+        (0..).take_while(|i| won(last - i)).count() as u32
+    }
+
+    /// The last weeks until the given day, one row per week: `#` for a win, `x` for a loss and
+    /// `.` for a day not played
+    pub fn calendar(&self, today: Day) -> String {
+        let first = today.0 - CALENDAR_DAYS + 1;
+        (first..=today.0)
+            .map(|day| match self.results.get(&Day(day)) {
+                Some(result) if result.won => '#',
+                Some(_) => 'x',
+                None => '.',
+            })
+            .collect::<Vec<_>>()
+            .chunks(7)
+            .map(|week| week.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// A one line summary of the attempt of a day, to share it
+    pub fn share(&self, day: Day) -> Option<String> {
+        let result = self.results.get(&day)?;
+        let outcome = if result.won {
+            format!("cleared in {:.1}s", result.time.as_secs_f32())
+        } else {
+            format!("lost after {:.1}s", result.time.as_secs_f32())
+        };
+        Some(format!(
+            "Minesweeper daily {day}: {outcome}, {:.2} 3BV/s, {} clicks, streak {}",
+            result.speed(),
+            result.clicks,
+            self.streak(day)
+        ))
+    }
+}
+
+/// The daily board being played
+#[derive(Debug, Clone, Resource)]
+pub struct DailyGame {
+    pub day: Day,
+    /// Whether this is the first attempt of the day, the one that is recorded
+    pub official: bool,
+    pub clicks: u32,
+    /// Time of the first click
+    pub started: Option<Duration>,
+}
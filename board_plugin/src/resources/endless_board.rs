@@ -0,0 +1,53 @@
+use crate::board::CHUNK_SIZE;
+use crate::components::WorldCoordinate;
+use crate::endless::EndlessGame;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// The board of the endless mode. The origin tile is centered on the world origin
+#[derive(Debug, Resource)]
+pub struct EndlessBoard {
+    pub game: EndlessGame,
+    pub tile_size: f32,
+    pub tile_padding: f32,
+    /// Tile entities of the spawned chunks
+    pub tiles: HashMap<WorldCoordinate, Entity>,
+    /// Cover entities of the covered tiles of the spawned chunks
+    pub covers: HashMap<WorldCoordinate, Entity>,
+    /// Chunks spawned because they are in view, by chunk coordinates
+    pub chunks: HashMap<(i32, i32), Entity>,
+    /// Where the camera is heading after a reveal
+    pub focus: Option<Vec2>,
+    pub entity: Entity,
+}
+
+impl EndlessBoard {
+    /// Center of a tile in world coordinates
+    pub fn tile_position(&self, coord: WorldCoordinate) -> Vec2 {
+        Vec2::new(coord.x as f32, coord.y as f32) * self.tile_size
+    }
+
+    /// Tile under a position in world coordinates
+    pub fn mouse_position(&self, position: Vec2) -> WorldCoordinate {
+        let tile = (position / self.tile_size).round();
+        WorldCoordinate {
+            x: tile.x as i32,
+            y: tile.y as i32,
+        }
+    }
+
+    pub fn chunk_tiles((x, y): (i32, i32)) -> impl Iterator<Item = WorldCoordinate> {
+        let size = CHUNK_SIZE as i32;
+        (y * size..(y + 1) * size)
+            .flat_map(move |y| (x * size..(x + 1) * size).map(move |x| WorldCoordinate { x, y }))
+    }
+
+    /// Chunks overlapping an area in world coordinates
+    pub fn chunks_in(&self, area: Rect) -> Vec<(i32, i32)> {
+        let min = self.mouse_position(area.min).chunk();
+        let max = self.mouse_position(area.max).chunk();
+        (min.1..=max.1)
+            .flat_map(|y| (min.0..=max.0).map(move |x| (x, y)))
+            .collect()
+    }
+}
@@ -0,0 +1,21 @@
+use bevy::ecs::system::Resource;
+use serde::{Deserialize, Serialize};
+
+/// Plays the endless mode instead of a fixed board when inserted, see `endless`
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct EndlessOptions {
+    /// Seed of the world, random for each game if unset
+    pub seed: Option<u64>,
+    /// Mines in each chunk of `CHUNK_SIZE` x `CHUNK_SIZE` tiles
+    pub bombs_per_chunk: u32,
+}
+
+impl Default for EndlessOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            // The density of an intermediate board
+            bombs_per_chunk: 40,
+        }
+    }
+}
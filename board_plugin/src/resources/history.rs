@@ -0,0 +1,52 @@
+use crate::board::Mark;
+use crate::components::Coordinate;
+
+#[derive(Debug, Clone)]
+pub enum Move {
+    /// Every tile uncovered by a single click, with the mark it had before
+    Reveal(Vec<(Coordinate, Option<Mark>)>),
+    /// A mark change on a single covered tile
+    Mark {
+        coord: Coordinate,
+        from: Option<Mark>,
+        to: Option<Mark>,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    moves: Vec<Move>,
+    undone: Vec<Move>,
+}
+
+impl History {
+    /// Records a new move, dropping the moves that could have been redone
+    pub fn push(&mut self, m: Move) {
+        self.moves.push(m);
+        self.undone.clear();
+    }
+
+    /// Adds an uncovered tile to the reveal in progress
+    pub fn record_reveal(&mut self, coord: Coordinate, mark: Option<Mark>) {
+        if let Some(Move::Reveal(tiles)) = self.moves.last_mut() {
+            tiles.push((coord, mark));
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<Move> {
+        let m = self.moves.pop()?;
+        self.undone.push(m.clone());
+        Some(m)
+    }
+
+    /// Pops the last undone move. Reveals are replayed through the regular uncover systems,
+    /// so an empty reveal is re-opened to collect them again.
+    pub fn redo(&mut self) -> Option<Move> {
+        let m = self.undone.pop()?;
+        match &m {
+            Move::Reveal(_) => self.moves.push(Move::Reveal(Vec::new())),
+            Move::Mark { .. } => self.moves.push(m.clone()),
+        }
+        Some(m)
+    }
+}
@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Keys used to play the board with the keyboard. Undo and redo are pressed with Ctrl
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct KeyBindings {
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub reveal: KeyCode,
+    pub flag: KeyCode,
+    pub undo: KeyCode,
+    pub redo: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            up: KeyCode::ArrowUp,
+            down: KeyCode::ArrowDown,
+            left: KeyCode::ArrowLeft,
+            right: KeyCode::ArrowRight,
+            reveal: KeyCode::Space,
+            flag: KeyCode::KeyF,
+            undo: KeyCode::KeyZ,
+            redo: KeyCode::KeyY,
+        }
+    }
+}
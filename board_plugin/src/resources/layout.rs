@@ -0,0 +1,114 @@
+use crate::components::Coordinate;
+use crate::tile_map::TileMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Fixed mine positions for a board.
+///
+/// As text, a layout is a grid where `*` is a mine and `.` a safe tile, the first line being
+/// the top row of the board.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Layout {
+    pub width: u16,
+    pub height: u16,
+    pub mines: Vec<Coordinate>,
+}
+
+#[derive(Debug, Error)]
+pub enum LayoutError {
+    #[error("Could not access layout: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("The layout is empty")]
+    Empty,
+    #[error("Line {0} does not have the same length as the first one")]
+    UnevenRows(usize),
+    #[error("Unexpected character {0:?} on line {1}")]
+    InvalidTile(char, usize),
+    #[error("The layout is too large")]
+    TooLarge,
+}
+
+impl Layout {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, LayoutError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), LayoutError> {
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// Moves a mine to a tile without one, e.g. like the safe start of a board
+    pub(crate) fn move_mine(&mut self, from: Coordinate, to: Coordinate) -> bool {
+        if self.mines.contains(&to) {
+            return false;
+        }
+        let Some(mine) = self.mines.iter_mut().find(|mine| **mine == from) else {
+            return false;
+        };
+        *mine = to;
+        true
+    }
+
+    pub(crate) fn from_tile_map(tile_map: &TileMap) -> Self {
+        Self {
+            width: tile_map.width(),
+            height: tile_map.height(),
+            mines: tile_map.bomb_coordinates(),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = LayoutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let width = rows.first().ok_or(LayoutError::Empty)?.chars().count();
+        let height = rows.len();
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(LayoutError::TooLarge);
+        }
+        let mut mines = Vec::new();
+        for (line, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(LayoutError::UnevenRows(line + 1));
+            }
+            for (x, c) in row.chars().enumerate() {
+                match c {
+                    '*' => mines.push(Coordinate {
+                        x: x as u16,
+                        y: (height - 1 - line) as u16,
+                    }),
+                    '.' => (),
+                    _ => return Err(LayoutError::InvalidTile(c, line + 1)),
+                }
+            }
+        }
+        Ok(Self {
+            width: width as u16,
+            height: height as u16,
+            mines,
+        })
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                let mine = self.mines.contains(&Coordinate { x, y });
+                write!(f, "{}", if mine { '*' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
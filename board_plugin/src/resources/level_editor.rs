@@ -0,0 +1,53 @@
+use crate::components::Coordinate;
+use crate::{Layout, StartPosition};
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+/// Smallest and largest sides of an edited board
+pub const EDITOR_SIZES: (u16, u16) = (2, 100);
+
+/// Places mines by hand on a board, offered in the menu when inserted
+#[derive(Debug, Clone, Resource)]
+pub struct LevelEditor {
+    /// Where the layout is saved
+    pub path: PathBuf,
+    /// The layout being edited
+    pub layout: Layout,
+    /// Result of the last action, shown under the shortcuts
+    pub(crate) status: String,
+}
+
+impl LevelEditor {
+    pub fn new(path: impl Into<PathBuf>, layout: Layout) -> Self {
+        Self {
+            path: path.into(),
+            layout,
+            status: String::new(),
+        }
+    }
+
+    /// Changes the size of the board by the given number of columns and rows, the mines left
+    /// outside are dropped
+    pub(crate) fn resize(&mut self, dx: i32, dy: i32) {
+        let (min, max) = EDITOR_SIZES;
+        let side = |side: u16, delta: i32| (side as i32 + delta).clamp(min as i32, max as i32);
+        let (width, height) = (
+            side(self.layout.width, dx) as u16,
+            side(self.layout.height, dy) as u16,
+        );
+        self.layout.mines.retain(|c| c.x < width && c.y < height);
+        self.layout.width = width;
+        self.layout.height = height;
+    }
+
+    /// Every tile revealed, so that the mines and numbers are shown
+    pub(crate) fn start(&self) -> StartPosition {
+        let (width, height) = (self.layout.width, self.layout.height);
+        StartPosition {
+            revealed: (0..height)
+                .flat_map(|y| (0..width).map(move |x| Coordinate { x, y }))
+                .collect(),
+            flagged: Vec::new(),
+        }
+    }
+}
@@ -1,6 +1,13 @@
+pub(crate) mod bit_set;
 pub(crate) mod tile;
 pub(crate) mod tile_map;
+pub(crate) mod tile_states;
+pub(crate) mod chunked_tile_map;
 pub(crate) mod board;
+pub use board::{Board, Mark};
+pub(crate) mod tile_atlas;
+pub use tile_atlas::TileAtlas;
+pub(crate) mod history;
 
 pub mod button_style;
 pub use button_style::ButtonStyle;
@@ -10,5 +17,54 @@ pub mod board_assets;
 pub use board_assets::BoardAssets;
 pub use board_assets::SpriteMaterial;
 
+pub mod board_audio;
+pub use board_audio::AudioSettings;
+pub use board_audio::BoardAudio;
+
 mod board_options;
-pub use board_options::*;
\ No newline at end of file
+pub use board_options::*;
+
+mod number_palette;
+pub use number_palette::*;
+
+mod animation_options;
+pub use animation_options::*;
+
+mod key_bindings;
+pub use key_bindings::*;
+
+mod settings;
+pub use settings::*;
+
+mod layout;
+pub use layout::*;
+
+mod replay;
+pub use replay::*;
+
+mod bot_player;
+pub use bot_player::*;
+
+mod endless_options;
+pub use endless_options::*;
+
+mod endless_board;
+pub use endless_board::*;
+
+mod race;
+pub use race::*;
+
+mod net_client;
+pub use net_client::*;
+
+mod spectator;
+pub use spectator::*;
+
+mod daily;
+pub use daily::*;
+
+mod puzzle;
+pub use puzzle::*;
+
+mod level_editor;
+pub use level_editor::*;
@@ -0,0 +1,48 @@
+use crate::net::{ClientMessage, Connection, NetError, NetGame, ServerMessage};
+use crate::BoardOptions;
+use bevy::prelude::*;
+use std::net::ToSocketAddrs;
+
+/// Connection to a `minesweeper_server`. When inserted, moves are sent to the server and the
+/// board only changes with what it sends back
+#[derive(Resource)]
+pub struct NetClient {
+    pub game: NetGame,
+    /// Percentage of the safe tiles each player revealed
+    pub progress: Vec<u8>,
+    connection: Connection<ServerMessage>,
+}
+
+impl NetClient {
+    /// Connects to a server and waits for the first game, which starts once every player joined
+    pub fn connect(address: impl ToSocketAddrs) -> Result<Self, NetError> {
+        let connection = Connection::connect(address)?;
+        let game = match connection.receive()? {
+            ServerMessage::Start(game) => game,
+            message => return Err(NetError::Unexpected(format!("{message:?}"))),
+        };
+        Ok(Self {
+            progress: vec![0; game.players],
+            game,
+            connection,
+        })
+    }
+
+    /// The options of the current game: its layout, without undo
+    pub fn board_options(&self, options: BoardOptions) -> BoardOptions {
+        BoardOptions {
+            layout: Some(self.game.layout.clone()),
+            practice_mode: false,
+            ..options
+        }
+    }
+
+    pub(crate) fn send(&self, message: &ClientMessage) -> Result<(), NetError> {
+        self.connection.send(message)
+    }
+
+    /// The next message received, if any
+    pub(crate) fn try_receive(&self) -> Result<Option<ServerMessage>, NetError> {
+        self.connection.try_receive()
+    }
+}
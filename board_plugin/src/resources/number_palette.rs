@@ -0,0 +1,77 @@
+use crate::BoardAssets;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaletteKind {
+    /// The number colors of the current theme
+    Theme,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+impl PaletteKind {
+    pub const ALL: [PaletteKind; 5] = [
+        PaletteKind::Theme,
+        PaletteKind::Deuteranopia,
+        PaletteKind::Protanopia,
+        PaletteKind::Tritanopia,
+        PaletteKind::HighContrast,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|k| *k == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Colors of the numbers 1 to 8. They are dark enough to be read on the tiles of every
+    /// bundled theme, from the mid grey of the default one to the white of the high contrast one
+    fn colors(self) -> Option<[&'static str; 8]> {
+        match self {
+            PaletteKind::Theme => None,
+            PaletteKind::Deuteranopia => Some([
+                "0030b0", "6a3000", "000000", "5a2a82", "4d4000", "000080", "702850", "004040",
+            ]),
+            PaletteKind::Protanopia => Some([
+                "0030b0", "4d4000", "000080", "6a3000", "5a2a82", "004040", "000000", "702850",
+            ]),
+            PaletteKind::Tritanopia => Some([
+                "8b0000", "004a4a", "800050", "000000", "5c2e00", "003c00", "5a005a", "303030",
+            ]),
+            PaletteKind::HighContrast => Some([
+                "0030b0", "004800", "8b0000", "000000", "700070", "5a3000", "004848", "000060",
+            ]),
+        }
+    }
+}
+
+/// How the neighbor counts are displayed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Resource)]
+pub struct NumberPalette {
+    pub kind: PaletteKind,
+    /// Displays a distinct shape next to each number
+    pub glyphs: bool,
+}
+
+impl Default for NumberPalette {
+    fn default() -> Self {
+        Self {
+            kind: PaletteKind::Theme,
+            glyphs: false,
+        }
+    }
+}
+
+impl NumberPalette {
+    pub fn color(&self, board_assets: &BoardAssets, count: u8) -> Color {
+        match self.kind.colors() {
+            Some(colors) => {
+                let index = (count.clamp(1, 8) - 1) as usize;
+                Color::hex(colors[index]).unwrap_or(Color::WHITE)
+            }
+            None => board_assets.bomb_counter_color(count),
+        }
+    }
+}
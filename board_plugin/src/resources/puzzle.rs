@@ -0,0 +1,239 @@
+use crate::components::Coordinate;
+use crate::tile_map::TileMap;
+use crate::{BoardOptions, Layout, LayoutError};
+use bevy::log;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Tiles revealed or flagged before the first move
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartPosition {
+    pub revealed: Vec<Coordinate>,
+    pub flagged: Vec<Coordinate>,
+}
+
+/// What has to be done to solve a puzzle
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PuzzleGoal {
+    /// Reveal every safe tile
+    Clear,
+    /// Reveal every safe tile, each of them being provably safe when revealed
+    NoGuess,
+    /// Flag every mine and nothing else
+    FlagMines,
+}
+
+impl Display for PuzzleGoal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PuzzleGoal::Clear => "Find all safe tiles",
+            PuzzleGoal::NoGuess => "Find all safe tiles without guessing",
+            PuzzleGoal::FlagMines => "Flag every mine",
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PuzzleError {
+    #[error("Could not read puzzles: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse puzzles: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error(transparent)]
+    Layout(#[from] LayoutError),
+    #[error(
+        "The tile on line {line}, column {column} shows {shown} but has {actual} mines around"
+    )]
+    WrongNumber {
+        line: usize,
+        column: usize,
+        shown: u8,
+        actual: u8,
+    },
+    #[error("{}: {source}", path.display())]
+    File {
+        path: PathBuf,
+        source: Box<PuzzleError>,
+    },
+}
+
+/// A hand-authored position to solve.
+///
+/// As text, the board is a grid like a `Layout` where `*` is a covered mine, `.` a covered safe
+/// tile, `F` a flagged mine and a digit a revealed tile showing that number of mines around.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PuzzleText", into = "PuzzleText")]
+pub struct Puzzle {
+    pub name: String,
+    pub goal: PuzzleGoal,
+    pub layout: Layout,
+    pub start: StartPosition,
+}
+
+/// A puzzle as written in a pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PuzzleText {
+    name: String,
+    goal: PuzzleGoal,
+    board: String,
+}
+
+impl TryFrom<PuzzleText> for Puzzle {
+    type Error = PuzzleError;
+
+    fn try_from(text: PuzzleText) -> Result<Self, Self::Error> {
+        let rows: Vec<&str> = text
+            .board
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        // The mines and the size are read like a layout, the rest of the tiles being safe
+        let layout: Layout = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .map(|c| match c {
+                        'F' => '*',
+                        '0'..='8' => '.',
+                        c => c,
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            .parse()?;
+        let mut tile_map = TileMap::create(layout.width, layout.height);
+        tile_map.set_bombs_at(&layout.mines);
+        let mut start = StartPosition::default();
+        for (line, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let coord = Coordinate {
+                    x: x as u16,
+                    y: layout.height - 1 - line as u16,
+                };
+                if c == 'F' {
+                    start.flagged.push(coord);
+                }
+                let Some(shown) = c.to_digit(10) else {
+                    continue;
+                };
+                let actual = tile_map.bomb_count_at(coord);
+                if shown as u8 != actual {
+                    return Err(PuzzleError::WrongNumber {
+                        line: line + 1,
+                        column: x + 1,
+                        shown: shown as u8,
+                        actual,
+                    });
+                }
+                start.revealed.push(coord);
+            }
+        }
+        Ok(Self {
+            name: text.name,
+            goal: text.goal,
+            layout,
+            start,
+        })
+    }
+}
+
+impl From<Puzzle> for PuzzleText {
+    fn from(puzzle: Puzzle) -> Self {
+        let mut tile_map = TileMap::create(puzzle.layout.width, puzzle.layout.height);
+        tile_map.set_bombs_at(&puzzle.layout.mines);
+        let mut board = String::new();
+        for y in (0..puzzle.layout.height).rev() {
+            for x in 0..puzzle.layout.width {
+                let coord = Coordinate { x, y };
+                board.push(if puzzle.start.flagged.contains(&coord) {
+                    'F'
+                } else if tile_map.is_bomb_at(coord) {
+                    '*'
+                } else if puzzle.start.revealed.contains(&coord) {
+                    char::from(b'0' + tile_map.bomb_count_at(coord))
+                } else {
+                    '.'
+                });
+            }
+            board.push('\n');
+        }
+        Self {
+            name: puzzle.name,
+            goal: puzzle.goal,
+            board,
+        }
+    }
+}
+
+impl Puzzle {
+    /// The options of the puzzle board, without undo
+    pub fn board_options(&self, options: BoardOptions) -> BoardOptions {
+        BoardOptions {
+            layout: Some(self.layout.clone()),
+            start: Some(self.start.clone()),
+            practice_mode: false,
+            band: None,
+            ..options
+        }
+    }
+}
+
+/// Puzzles stored together as RON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PuzzlePack {
+    pub name: String,
+    pub puzzles: Vec<Puzzle>,
+}
+
+impl PuzzlePack {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, PuzzleError> {
+        Ok(ron::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// The puzzle packs offered in the menu when inserted
+#[derive(Debug, Clone, Default, Resource)]
+pub struct PuzzlePacks {
+    pub packs: Vec<PuzzlePack>,
+}
+
+impl PuzzlePacks {
+    /// Reads every `.ron` pack of a directory, sorted by file name. Packs that cannot be read
+    /// are skipped with a warning so the others can still be played
+    pub fn read_dir(dir: impl AsRef<Path>) -> Result<Self, PuzzleError> {
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>, std::io::Error>>()?;
+        paths.retain(|path| path.extension().is_some_and(|e| e == "ron"));
+        paths.sort();
+        let packs = paths
+            .into_iter()
+            .filter_map(|path| match PuzzlePack::read(&path) {
+                Ok(pack) => Some(pack),
+                Err(e) => {
+                    let e = PuzzleError::File {
+                        path,
+                        source: Box::new(e),
+                    };
+                    log::warn!("Skipping puzzle pack {e}");
+                    None
+                }
+            })
+            .collect();
+        Ok(Self { packs })
+    }
+}
+
+/// The puzzle being played
+#[derive(Debug, Clone, Resource)]
+pub struct PuzzleGame {
+    pub puzzle: Puzzle,
+    /// Set once a tile that could be a mine is revealed
+    pub guessed: bool,
+}
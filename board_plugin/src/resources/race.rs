@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Plays a two-player race on identical boards instead of a single board when inserted
+#[derive(Debug, Clone, Default, Resource)]
+pub struct RaceOptions;
+
+/// The race in progress
+#[derive(Debug, Clone, Resource)]
+pub struct Race {
+    /// Seed of both boards
+    pub seed: u64,
+    pub started: Duration,
+    /// Time taken by each player, once they are done
+    pub times: [Option<Duration>; 2],
+    pub winner: Option<usize>,
+}
+
+impl Race {
+    pub const PLAYERS: [&'static str; 2] = ["PLAYER 1 (mouse)", "PLAYER 2 (keyboard)"];
+
+    /// Time played by a player so far
+    pub fn time(&self, player: usize, now: Duration) -> Duration {
+        self.times[player].unwrap_or(now.saturating_sub(self.started))
+    }
+}
@@ -0,0 +1,87 @@
+use crate::components::Coordinate;
+use crate::Layout;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayAction {
+    Reveal(Coordinate),
+    Mark(Coordinate),
+    Undo,
+    Redo,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct ReplayMove {
+    /// Seconds since the start of the game
+    pub time: f32,
+    pub action: ReplayAction,
+}
+
+/// A recorded game, stored as RON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub layout: Layout,
+    pub moves: Vec<ReplayMove>,
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Could not access replay: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse replay: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("Could not serialize replay: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+impl Replay {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        Ok(ron::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        fs::write(path, ron::ser::to_string_pretty(self, Default::default())?)?;
+        Ok(())
+    }
+}
+
+/// Records the moves of each game, the file is overwritten when a game ends
+#[derive(Debug, Resource)]
+pub struct ReplayRecorder {
+    pub path: PathBuf,
+    pub(crate) replay: Option<Replay>,
+    pub(crate) elapsed: f32,
+}
+
+impl ReplayRecorder {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            replay: None,
+            elapsed: 0.,
+        }
+    }
+}
+
+/// Plays the moves of a replay back instead of the mouse. The board layout has to be set to the
+/// replay one through `BoardOptions::layout`
+#[derive(Debug, Resource)]
+pub struct ReplayPlayback {
+    pub(crate) replay: Replay,
+    pub(crate) next: usize,
+    pub(crate) elapsed: f32,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next: 0,
+            elapsed: 0.,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{AudioSettings, KeyBindings, NumberPalette, TileSize};
+use bevy::log;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// User preferences edited from the settings screen and saved in the platform config directory
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+#[serde(default)]
+pub struct Settings {
+    pub tile_padding: f32,
+    pub tile_size: TileSize,
+    pub safe_start: bool,
+    pub question_marks: bool,
+    pub animations: bool,
+    /// Index in the `Themes` list
+    pub theme: usize,
+    pub palette: NumberPalette,
+    pub key_bindings: KeyBindings,
+    pub audio: AudioSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            tile_padding: 1.,
+            tile_size: Default::default(),
+            safe_start: true,
+            question_marks: true,
+            animations: true,
+            theme: 0,
+            palette: Default::default(),
+            key_bindings: Default::default(),
+            audio: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("No config directory on this platform")]
+    NoConfigDir,
+    #[error("Could not write settings: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not serialize settings: {0}")]
+    Ron(#[from] ron::Error),
+}
+
+impl Settings {
+    /// `<config dir>/minesweeper/settings.ron`
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("minesweeper").join("settings.ron"))
+    }
+
+    /// Reads the saved settings, falling back to the defaults if there are none or they are
+    /// invalid
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match ron::from_str(&text) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::warn!("Ignoring invalid settings in {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), SettingsError> {
+        let path = Self::path().ok_or(SettingsError::NoConfigDir)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, Default::default())?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
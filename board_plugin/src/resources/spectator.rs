@@ -0,0 +1,156 @@
+use crate::components::Coordinate;
+use crate::net::{Connection, NetError, SpectatorMessage, Writer};
+use crate::{Layout, Replay, ReplayAction, ReplayMove};
+use bevy::log;
+use bevy::prelude::*;
+use std::io::ErrorKind;
+use std::net::{TcpListener, ToSocketAddrs};
+
+/// Lets spectators connect and watch the games as they are played
+#[derive(Debug, Resource)]
+pub struct SpectatorHost {
+    listener: TcpListener,
+    /// Written on their own thread each, the ones who left are dropped at the next message
+    spectators: Vec<Writer<SpectatorMessage>>,
+    /// The current game, sent to the spectators joining late
+    replay: Option<Replay>,
+    question_marks: bool,
+    pub(crate) elapsed: f32,
+}
+
+impl SpectatorHost {
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Self, NetError> {
+        let listener = TcpListener::bind(address)?;
+        // Polled every frame
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            spectators: Vec::new(),
+            replay: None,
+            question_marks: false,
+            elapsed: 0.,
+        })
+    }
+
+    /// Accepts the pending spectators and catches them up with the current game
+    pub(crate) fn accept(&mut self) {
+        loop {
+            let (stream, address) = match self.listener.accept() {
+                Ok(connection) => connection,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return,
+                Err(e) => {
+                    log::error!("Could not accept a spectator: {}", e);
+                    return;
+                }
+            };
+            // The writer thread waits for the spectator to read
+            if let Err(e) = stream.set_nonblocking(false) {
+                log::error!("Could not set up the connection to {}: {}", address, e);
+                continue;
+            }
+            let spectator = Writer::new(stream);
+            let caught_up = match &self.replay {
+                Some(replay) => Self::catch_up(&spectator, replay, self.question_marks),
+                None => Ok(()),
+            };
+            match caught_up {
+                Ok(()) => {
+                    log::info!("Spectator joined from {}", address);
+                    self.spectators.push(spectator);
+                }
+                Err(e) => log::error!("Could not send the game to {}: {}", address, e),
+            }
+        }
+    }
+
+    fn catch_up(
+        spectator: &Writer<SpectatorMessage>,
+        replay: &Replay,
+        question_marks: bool,
+    ) -> Result<(), NetError> {
+        spectator.send(SpectatorMessage::Start {
+            layout: replay.layout.clone(),
+            question_marks,
+        })?;
+        for m in &replay.moves {
+            spectator.send(SpectatorMessage::Move(m.action))?;
+        }
+        Ok(())
+    }
+
+    /// Starts broadcasting a new board
+    pub(crate) fn start(&mut self, layout: Layout, question_marks: bool) {
+        self.broadcast(&SpectatorMessage::Start {
+            layout: layout.clone(),
+            question_marks,
+        });
+        self.replay = Some(Replay {
+            layout,
+            moves: Vec::new(),
+        });
+        self.question_marks = question_marks;
+        self.elapsed = 0.;
+    }
+
+    /// Follows a mine moved by the safe start of the board: the spectators start again from the
+    /// new layout, followed by the moves played so far
+    pub(crate) fn move_mine(&mut self, from: Coordinate, to: Coordinate) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        if !replay.layout.move_mine(from, to) {
+            return;
+        }
+        let question_marks = self.question_marks;
+        self.spectators
+            .retain(|spectator| Self::catch_up(spectator, replay, question_marks).is_ok());
+    }
+
+    pub(crate) fn push(&mut self, action: ReplayAction) {
+        self.broadcast(&SpectatorMessage::Move(action));
+        let time = self.elapsed;
+        if let Some(replay) = &mut self.replay {
+            replay.moves.push(ReplayMove { time, action });
+        }
+    }
+
+    /// Queues a message for every spectator, the ones who left are dropped
+    fn broadcast(&mut self, message: &SpectatorMessage) {
+        self.spectators
+            .retain(|spectator| spectator.send(message.clone()).is_ok());
+    }
+}
+
+/// Watches the game of a `SpectatorHost` instead of playing
+#[derive(Resource)]
+pub struct SpectatorClient {
+    /// Shows where the mines are, e.g. to comment the game
+    pub show_mines: bool,
+    connection: Connection<SpectatorMessage>,
+    /// A message received early, read again first
+    pending: Option<SpectatorMessage>,
+}
+
+impl SpectatorClient {
+    /// Connects to the game, its board is received right away
+    pub fn connect(address: impl ToSocketAddrs, show_mines: bool) -> Result<Self, NetError> {
+        Ok(Self {
+            show_mines,
+            connection: Connection::connect(address)?,
+            pending: None,
+        })
+    }
+
+    /// The next message received, if any
+    pub(crate) fn try_receive(&mut self) -> Result<Option<SpectatorMessage>, NetError> {
+        match self.pending.take() {
+            Some(message) => Ok(Some(message)),
+            None => self.connection.try_receive(),
+        }
+    }
+
+    /// Keeps a message to be received again on the next frame
+    pub(crate) fn put_back(&mut self, message: SpectatorMessage) {
+        self.pending = Some(message);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::BoardAssets;
+use ab_glyph::{Font as _, ScaleFont as _};
+use bevy::asset::{LoadState, UntypedAssetId};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Font size the numbers are rasterized at, they are then scaled to the tiles
+const DIGIT_SIZE: f32 = 64.;
+
+/// The textures of `BoardAssets` and the numbers of the counter font packed in a single image,
+/// so that each chunk of the board is drawn as one mesh
+#[derive(Debug, Resource)]
+pub struct TileAtlas {
+    /// Draws the atlas, tinted by the colors of the mesh vertices
+    pub material: Handle<ColorMaterial>,
+    /// Area of each texture in the atlas, in UV coordinates
+    uvs: HashMap<AssetId<Image>, Rect>,
+    /// Area of the numbers 1 to 8 in the atlas, and their size relative to the font size
+    digits: Vec<(Rect, Vec2)>,
+}
+
+impl TileAtlas {
+    /// Packs the current textures and numbers, `None` while some of them are still loading.
+    /// The textures that failed to load are drawn plain, the numbers without a font are omitted
+    pub fn build(
+        board_assets: &BoardAssets,
+        asset_server: &AssetServer,
+        images: &mut Assets<Image>,
+        fonts: &Assets<Font>,
+        materials: &mut Assets<ColorMaterial>,
+    ) -> Option<Self> {
+        let loading =
+            |id: UntypedAssetId| asset_server.get_load_state(id) == Some(LoadState::Loading);
+        let sprites = [
+            &board_assets.tile_material,
+            &board_assets.covered_tile_material,
+            &board_assets.bomb_material,
+            &board_assets.exploded_bomb_material,
+            &board_assets.flag_material,
+            &board_assets.question_material,
+            &board_assets.wrong_flag_material,
+        ];
+        let mut textures: Vec<AssetId<Image>> = sprites
+            .iter()
+            .map(|material| material.texture.id())
+            .chain(board_assets.number_glyphs.iter().map(Handle::id))
+            .collect();
+        textures.sort_unstable();
+        textures.dedup();
+        if textures.iter().any(|id| loading(id.untyped())) {
+            return None;
+        }
+        let font = fonts.get(&board_assets.bomb_counter_font);
+        if font.is_none() && loading(board_assets.bomb_counter_font.id().untyped()) {
+            return None;
+        }
+        let digits: Vec<Image> = font
+            .and_then(|font| (1..=8).map(|count| digit_image(font, count)).collect())
+            .unwrap_or_default();
+
+        // The default image is a white pixel, also standing for the missing textures
+        let white = Handle::<Image>::default().id();
+        let mut builder = TextureAtlasBuilder::default()
+            .padding(UVec2::splat(2))
+            .max_size(Vec2::splat(4096.));
+        let mut placed = vec![white];
+        builder.add_texture(Some(white), images.get(white)?);
+        for id in &textures {
+            if let Some(image) = images.get(*id).filter(|_| *id != white) {
+                builder.add_texture(Some(*id), image);
+                placed.push(*id);
+            }
+        }
+        for digit in &digits {
+            builder.add_texture(None, digit);
+        }
+        let (layout, texture) = match builder.finish() {
+            Ok(atlas) => atlas,
+            Err(e) => {
+                log::error!("Failed to pack the tile textures: {e}");
+                return None;
+            }
+        };
+        // Half a texel inside the packed textures, so that their neighbors don't bleed in
+        let uv = |rect: Rect| {
+            Rect::from_corners(
+                (rect.min + 0.5) / layout.size,
+                (rect.max - 0.5) / layout.size,
+            )
+        };
+        let mut uvs: HashMap<AssetId<Image>, Rect> = placed
+            .iter()
+            .zip(&layout.textures)
+            .map(|(id, rect)| (*id, uv(*rect)))
+            .collect();
+        for id in textures {
+            let white = uvs[&white];
+            uvs.entry(id).or_insert(white);
+        }
+        let digits = layout.textures[placed.len()..]
+            .iter()
+            .map(|rect| (uv(*rect), rect.size() / DIGIT_SIZE))
+            .collect();
+        let material = materials.add(ColorMaterial {
+            color: Color::WHITE,
+            texture: Some(images.add(texture)),
+        });
+        Some(Self {
+            material,
+            uvs,
+            digits,
+        })
+    }
+
+    /// Area of a texture in the atlas, the plain white pixel if it wasn't packed
+    pub fn uv(&self, texture: &Handle<Image>) -> Rect {
+        self.uvs
+            .get(&texture.id())
+            .or_else(|| self.uvs.get(&Handle::<Image>::default().id()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Area of a number in the atlas and its size relative to the font size
+    pub fn digit(&self, count: u8) -> Option<(Rect, Vec2)> {
+        self.digits.get(count.checked_sub(1)? as usize).copied()
+    }
+}
+
+/// White number with a transparent background, tinted by the mesh
+fn digit_image(font: &Font, count: u8) -> Option<Image> {
+    let character = char::from_digit(count as u32, 10)?;
+    let glyph = font.font.as_scaled(DIGIT_SIZE).scaled_glyph(character);
+    let outlined = font.font.outline_glyph(glyph)?;
+    Some(Font::get_outlined_glyph_texture(outlined))
+}
@@ -1,17 +1,27 @@
+use crate::bit_set::BitSet;
+use crate::bot::rating;
 use crate::components::Coordinate;
 use crate::resources::tile::Tile;
-use rand::{thread_rng, Rng};
-use std::ops::{Deref, DerefMut};
+use crate::BoardOptions;
+use bevy::log;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
 
-#[derive(Debug)]
+/// Mines of a board, stored row by row in flat arrays
+#[derive(Debug, Clone)]
 pub struct TileMap {
-    bomb_count: u16,
+    bomb_count: u32,
     height: u16,
     width: u16,
-    map: Vec<Vec<Tile>>,
+    bombs: BitSet,
+    /// Number of neighboring bombs of each tile
+    neighbors: Vec<u8>,
 }
 
-const SQUARE_COORDINATES: [(i8, i8); 8] = [
+/// Boards generated to find one in the requested difficulty band
+const MAX_BAND_ATTEMPTS: usize = 200;
+
+pub(crate) const SQUARE_COORDINATES: [(i8, i8); 8] = [
     (-1, -1), // Bottom left
     (0, -1),  // Bottom
     (1, -1),  // Bottom right
@@ -24,30 +34,74 @@ const SQUARE_COORDINATES: [(i8, i8); 8] = [
 
 impl TileMap {
     pub fn create(width: u16, height: u16) -> Self {
-        let map = (0..height)
-            .into_iter()
-            .map(|_| (0..width).into_iter().map(|_| Tile::Empty).collect())
-            .collect();
+        let len = width as usize * height as usize;
         Self {
             bomb_count: 0,
             height,
             width,
-            map,
+            bombs: BitSet::new(len),
+            neighbors: vec![0; len],
         }
     }
 
+    /// Creates the map described by the options: their layout if any, otherwise randomly
+    /// placed bombs, rated until they fall in the difficulty band if one is requested
+    pub fn from_options(options: &BoardOptions) -> Self {
+        if let Some(layout) = &options.layout {
+            let mut tile_map = Self::create(layout.width, layout.height);
+            tile_map.set_bombs_at(&layout.mines);
+            return tile_map;
+        }
+        let (width, height) = options.map_size;
+        let Some(band) = options.band else {
+            let mut tile_map = Self::create(width, height);
+            match options.seed {
+                Some(seed) => tile_map
+                    .set_bombs_with_rng(options.bomb_count, &mut StdRng::seed_from_u64(seed)),
+                None => tile_map.set_bombs(options.bomb_count),
+            }
+            return tile_map;
+        };
+        let mut rng = match options.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        // Some bands can't be reached with a given size and bomb count, the closest board is
+        // kept then
+        let mut closest: Option<(Self, f32)> = None;
+        for _ in 0..MAX_BAND_ATTEMPTS {
+            let mut tile_map = Self::create(width, height);
+            tile_map.set_bombs_with_rng(options.bomb_count, &mut rng);
+            let distance = band.distance(rating::rate(&tile_map).score);
+            if distance == 0. {
+                return tile_map;
+            }
+            if closest.as_ref().is_none_or(|(_, d)| distance < *d) {
+                closest = Some((tile_map, distance));
+            }
+        }
+        log::warn!("no board found in the {band} band, using the closest one");
+        closest
+            .map(|(tile_map, _)| tile_map)
+            .expect("at least one board is generated")
+    }
+
     #[cfg(feature = "inspect")]
     pub fn console_output(&self) -> String {
         let mut buffer = format!(
             "Map ({}, {}) with {} bombs:\n",
             self.width, self.height, self.bomb_count
         );
-        let line: String = (0..=(self.width + 1)).into_iter().map(|_| '-').collect();
+        let line: String = (0..=(self.width + 1)).map(|_| '-').collect();
         buffer = format!("{}{}\n", buffer, line);
-        for line in self.iter().rev() {
+        for y in (0..self.height).rev() {
             buffer = format!("{}|", buffer);
-            for tile in line.iter() {
-                buffer = format!("{}{}", buffer, tile.console_output());
+            for x in 0..self.width {
+                buffer = format!(
+                    "{}{}",
+                    buffer,
+                    self.tile(Coordinate { x, y }).console_output()
+                );
             }
             buffer = format!("{}|\n", buffer);
         }
@@ -62,10 +116,16 @@ impl TileMap {
         self.height
     }
 
-    pub fn bomb_count(&self) -> u16 {
+    pub fn bomb_count(&self) -> u32 {
         self.bomb_count
     }
 
+    /// Position of a tile in the flat arrays, `None` outside of the map
+    fn index(&self, coordinate: Coordinate) -> Option<usize> {
+        (coordinate.x < self.width && coordinate.y < self.height)
+            .then(|| coordinate.y as usize * self.width as usize + coordinate.x as usize)
+    }
+
     pub fn safe_square_at(&self, coordinate: Coordinate) -> impl Iterator<Item = Coordinate> {
         SQUARE_COORDINATES
             .iter()
@@ -74,63 +134,114 @@ impl TileMap {
     }
 
     pub fn is_bomb_at(&self, coordinate: Coordinate) -> bool {
-        if coordinate.x >= self.width || coordinate.y >= self.height {
-            return false;
-        };
-        self.map[coordinate.y as usize][coordinate.x as usize].is_bomb()
+        self.index(coordinate)
+            .is_some_and(|index| self.bombs.contains(index))
     }
 
     pub fn bomb_count_at(&self, coordinate: Coordinate) -> u8 {
+        match self.index(coordinate) {
+            Some(index) if !self.bombs.contains(index) => self.neighbors[index],
+            _ => 0,
+        }
+    }
+
+    pub fn tile(&self, coordinate: Coordinate) -> Tile {
         if self.is_bomb_at(coordinate) {
-            return 0;
+            return Tile::Bomb;
+        }
+        match self.bomb_count_at(coordinate) {
+            0 => Tile::Empty,
+            count => Tile::BombNeighbor(count),
         }
-        let res = self
-            .safe_square_at(coordinate)
-            .filter(|coord| self.is_bomb_at(*coord))
-            .count();
-        res as u8
     }
 
-    pub fn set_bombs(&mut self, bomb_count: u16) {
-        self.bomb_count = bomb_count;
+    /// Every tile, row by row from the bottom
+    pub fn tiles(&self) -> impl Iterator<Item = (Coordinate, Tile)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| Coordinate { x, y }))
+            .map(|coord| (coord, self.tile(coord)))
+    }
+
+    pub fn set_bombs(&mut self, bomb_count: u32) {
+        self.set_bombs_with_rng(bomb_count, &mut thread_rng());
+    }
+
+    /// Places the bombs randomly using `rng`, a seeded generator always gives the same board
+    pub fn set_bombs_with_rng(&mut self, bomb_count: u32, rng: &mut impl Rng) {
+        // At least one safe tile is kept so the placement ends
+        let tile_count = self.width as u32 * self.height as u32;
+        let bomb_count = bomb_count.min(tile_count.saturating_sub(1));
         let mut ramained_bombs = bomb_count;
-        let mut rng = thread_rng();
         while ramained_bombs > 0 {
-            let (x, y) = (
-                rng.gen_range(0..self.width) as usize,
-                rng.gen_range(0..self.height) as usize,
-            );
-            if let Tile::Empty = self[y][x] {
-                self[y][x] = Tile::Bomb;
-                ramained_bombs -= 1;
+            let (x, y) = (rng.gen_range(0..self.width), rng.gen_range(0..self.height));
+            if let Some(index) = self.index(Coordinate { x, y }) {
+                if self.bombs.insert(index) {
+                    ramained_bombs -= 1;
+                }
             }
         }
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let coord = Coordinate { x, y };
-                if self.is_bomb_at(coord) {
-                    continue;
-                }
-                let num = self.bomb_count_at(coord);
-                if num == 0 {
-                    continue;
-                }
-                let tile = &mut self[y as usize][x as usize];
-                *tile = Tile::BombNeighbor(num);
+        self.set_neighbors();
+    }
+
+    /// Places the bombs at the given coordinates
+    pub fn set_bombs_at(&mut self, bombs: &[Coordinate]) {
+        for coord in bombs {
+            if let Some(index) = self.index(*coord) {
+                self.bombs.insert(index);
             }
         }
+        self.set_neighbors();
     }
-}
 
-impl Deref for TileMap {
-    type Target = Vec<Vec<Tile>>;
-    fn deref(&self) -> &Self::Target {
-        &self.map
+    /// Adds or removes the bomb of a tile, returning whether it has one now
+    pub fn toggle_bomb(&mut self, coord: Coordinate) -> bool {
+        let Some(index) = self.index(coord) else {
+            return false;
+        };
+        let bomb = !self.bombs.remove(index);
+        if bomb {
+            self.bombs.insert(index);
+        }
+        self.set_neighbors();
+        bomb
+    }
+
+    /// Moves the bomb of a tile to the first tile without one, row by row from the bottom left
+    /// corner, returning where it went
+    pub fn move_bomb(&mut self, coord: Coordinate) -> Option<Coordinate> {
+        let index = self.index(coord).filter(|i| self.bombs.contains(*i))?;
+        let width = self.width as usize;
+        let target = (0..width * self.height as usize).find(|i| !self.bombs.contains(*i))?;
+        self.bombs.remove(index);
+        self.bombs.insert(target);
+        self.set_neighbors();
+        Some(Coordinate {
+            x: (target % width) as u16,
+            y: (target / width) as u16,
+        })
+    }
+
+    pub fn bomb_coordinates(&self) -> Vec<Coordinate> {
+        let width = self.width as usize;
+        self.bombs
+            .iter()
+            .map(|index| Coordinate {
+                x: (index % width) as u16,
+                y: (index / width) as u16,
+            })
+            .collect()
     }
-}
 
-impl DerefMut for TileMap {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.map
+    /// Counts the bombs and the neighboring bombs of every tile
+    fn set_neighbors(&mut self) {
+        self.bomb_count = self.bombs.count() as u32;
+        self.neighbors.fill(0);
+        for coord in self.bomb_coordinates() {
+            for neighbor in self.safe_square_at(coord) {
+                if let Some(index) = self.index(neighbor) {
+                    self.neighbors[index] += 1;
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,155 @@
+use crate::bit_set::BitSet;
+use crate::board::Mark;
+use crate::components::Coordinate;
+use crate::{BoardOptions, StartPosition};
+
+/// Covered and marked state of every tile, indexed row by row like the `TileMap`. Lookups and
+/// counts are O(1)
+#[derive(Debug, Clone)]
+pub struct TileStates {
+    width: u16,
+    height: u16,
+    covered: BitSet,
+    flags: BitSet,
+    questions: BitSet,
+    covered_count: u32,
+    flag_count: u32,
+}
+
+impl TileStates {
+    /// Every tile covered and unmarked
+    pub fn new(width: u16, height: u16) -> Self {
+        let len = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            covered: BitSet::full(len),
+            flags: BitSet::new(len),
+            questions: BitSet::new(len),
+            covered_count: len as u32,
+            flag_count: 0,
+        }
+    }
+
+    /// The tiles at the start of a game: every tile covered, unless the options start from a
+    /// position
+    pub fn from_options(width: u16, height: u16, options: &BoardOptions) -> Self {
+        let mut states = Self::new(width, height);
+        if let Some(start) = options.start.as_ref().filter(|_| options.layout.is_some()) {
+            states.apply_start(start);
+        }
+        states
+    }
+
+    fn apply_start(&mut self, start: &StartPosition) {
+        for coord in &start.revealed {
+            self.uncover(*coord);
+        }
+        for coord in &start.flagged {
+            if self.is_covered(*coord) {
+                self.set_mark(*coord, Some(Mark::Flag));
+            }
+        }
+    }
+
+    /// Position of a tile in the flat storage, `None` outside of the board
+    pub fn index(&self, coord: Coordinate) -> Option<usize> {
+        (coord.x < self.width && coord.y < self.height)
+            .then(|| coord.y as usize * self.width as usize + coord.x as usize)
+    }
+
+    pub fn coordinate(&self, index: usize) -> Coordinate {
+        Coordinate {
+            x: (index % self.width as usize) as u16,
+            y: (index / self.width as usize) as u16,
+        }
+    }
+
+    pub fn is_covered(&self, coord: Coordinate) -> bool {
+        self.index(coord).is_some_and(|i| self.covered.contains(i))
+    }
+
+    pub fn covered_count(&self) -> u32 {
+        self.covered_count
+    }
+
+    pub fn flag_count(&self) -> u32 {
+        self.flag_count
+    }
+
+    /// Covered tiles, row by row
+    pub fn covered(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.covered.iter().map(|i| self.coordinate(i))
+    }
+
+    /// Uncovers a tile and clears its mark, returning the mark if it was covered
+    pub fn uncover(&mut self, coord: Coordinate) -> Option<Option<Mark>> {
+        let index = self.index(coord)?;
+        if !self.covered.remove(index) {
+            return None;
+        }
+        self.covered_count -= 1;
+        let mark = self.mark(coord);
+        self.set_mark(coord, None);
+        Some(mark)
+    }
+
+    pub fn cover(&mut self, coord: Coordinate) {
+        if let Some(index) = self.index(coord) {
+            if self.covered.insert(index) {
+                self.covered_count += 1;
+            }
+        }
+    }
+
+    pub fn mark(&self, coord: Coordinate) -> Option<Mark> {
+        let index = self.index(coord)?;
+        if self.flags.contains(index) {
+            Some(Mark::Flag)
+        } else if self.questions.contains(index) {
+            Some(Mark::Question)
+        } else {
+            None
+        }
+    }
+
+    /// Cycles the mark of a covered tile: none -> flag -> question (if enabled) -> none.
+    /// Returns the previous and the new mark, `None` if the tile is uncovered
+    pub fn cycle_mark(
+        &mut self,
+        coord: Coordinate,
+        question_marks: bool,
+    ) -> Option<(Option<Mark>, Option<Mark>)> {
+        if !self.is_covered(coord) {
+            return None;
+        }
+        let from = self.mark(coord);
+        let to = match from {
+            None => Some(Mark::Flag),
+            Some(Mark::Flag) if question_marks => Some(Mark::Question),
+            Some(_) => None,
+        };
+        self.set_mark(coord, to);
+        Some((from, to))
+    }
+
+    pub fn set_mark(&mut self, coord: Coordinate, mark: Option<Mark>) {
+        let Some(index) = self.index(coord) else {
+            return;
+        };
+        if self.flags.remove(index) {
+            self.flag_count -= 1;
+        }
+        self.questions.remove(index);
+        match mark {
+            Some(Mark::Flag) => {
+                self.flags.insert(index);
+                self.flag_count += 1;
+            }
+            Some(Mark::Question) => {
+                self.questions.insert(index);
+            }
+            None => (),
+        }
+    }
+}
@@ -2,6 +2,10 @@ use bevy::ecs::schedule::States;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, States)]
 pub enum AppState {
+    Loading,
     InGame,
     Out,
+    Settings,
+    Puzzles,
+    Editor,
 }
\ No newline at end of file
@@ -0,0 +1,153 @@
+use crate::button_style::{ButtonStyle, ExitWindowTitle};
+use crate::events::AnnounceEvent;
+use crate::{Announcer, Board, RoundButton, TileCursor};
+use bevy::a11y::accesskit::{Action, Live, NodeBuilder, NodeId, Role};
+use bevy::a11y::{AccessibilityNode, ActionRequest, Focus};
+use bevy::input::ButtonInput;
+use bevy::prelude::*;
+use bevy_round_ui::prelude::RoundUiMaterial;
+
+/// Spawns the live region used to read out the board state
+pub fn setup_announcer(mut commands: Commands) {
+    let mut node = NodeBuilder::new(Role::StaticText);
+    node.set_live(Live::Assertive);
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Px(0.),
+                height: Val::Px(0.),
+                ..default()
+            },
+            ..default()
+        },
+        AccessibilityNode(node),
+        Announcer,
+        Name::new("Announcer"),
+    ));
+}
+
+/// Gives the keyboard focus back to the board when a game starts
+pub fn focus_announcer(mut focus: ResMut<Focus>, announcer: Query<Entity, With<Announcer>>) {
+    focus.0 = announcer.get_single().ok();
+}
+
+/// Keeps the cursor highlight on the cursor tile
+pub fn update_tile_cursor(board: Res<Board>, mut cursor: Query<&mut Transform, With<TileCursor>>) {
+    for mut transform in &mut cursor {
+        transform.translation.x = (board.cursor.x as f32 + 0.5) * board.tile_size;
+        transform.translation.y = (board.cursor.y as f32 + 0.5) * board.tile_size;
+    }
+}
+
+/// Announces the cursor tile whenever it or its state changes
+pub fn announce_cursor(
+    board: Res<Board>,
+    mut last: Local<String>,
+    mut announce_ewr: EventWriter<AnnounceEvent>,
+) {
+    let description = board.describe_tile(&board.cursor);
+    if *last != description {
+        *last = description.clone();
+        announce_ewr.send(AnnounceEvent(description));
+    }
+}
+
+/// Announces the result of the game, or the menu when the game was left
+pub fn announce_game_over(
+    board: Option<Res<Board>>,
+    title: Res<ExitWindowTitle>,
+    mut announce_ewr: EventWriter<AnnounceEvent>,
+) {
+    let mut text = title.text.clone();
+    if let Some(board) = board {
+        if let Some(coord) = board.exploded {
+            text = format!("{}, mine at {}", text, board.tile_position(&coord));
+        }
+    }
+    announce_ewr.send(AnnounceEvent(format!(
+        "{text}. Use tab to pick a menu entry and enter to select it"
+    )));
+}
+
+pub fn apply_announcements(
+    mut announce_evr: EventReader<AnnounceEvent>,
+    mut announcer: Query<&mut AccessibilityNode, With<Announcer>>,
+) {
+    let Some(event) = announce_evr.read().last() else {
+        return;
+    };
+    for mut node in &mut announcer {
+        node.set_name(event.0.clone());
+    }
+}
+
+/// Moves the focus between the visible menu buttons with tab and presses the focused one with
+/// enter or space
+pub fn navigate_menu(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut focus: ResMut<Focus>,
+    button_style: Res<ButtonStyle>,
+    mut buttons: Query<
+        (
+            Entity,
+            &Node,
+            &mut Interaction,
+            &mut Handle<RoundUiMaterial>,
+        ),
+        With<RoundButton>,
+    >,
+) {
+    if keys.any_just_pressed([KeyCode::Enter, KeyCode::Space]) {
+        if let Some(Ok((_, _, mut interaction, _))) = focus.0.map(|e| buttons.get_mut(e)) {
+            *interaction = Interaction::Pressed;
+        }
+        return;
+    }
+    if !keys.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    // Hidden buttons have no size
+    let visible: Vec<Entity> = buttons
+        .iter()
+        .filter(|(_, node, _, _)| node.size() != Vec2::ZERO)
+        .map(|(entity, ..)| entity)
+        .collect();
+    if visible.is_empty() {
+        return;
+    }
+    let backwards = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let next = match focus.0.and_then(|e| visible.iter().position(|v| *v == e)) {
+        None => 0,
+        Some(i) if backwards => (i + visible.len() - 1) % visible.len(),
+        Some(i) => (i + 1) % visible.len(),
+    };
+    for (entity, _, _, mut material) in &mut buttons {
+        *material = if entity == visible[next] {
+            button_style.hover.clone()
+        } else {
+            button_style.default.clone()
+        };
+    }
+    focus.0 = Some(visible[next]);
+}
+
+/// Handles the actions requested by assistive technologies on the menu buttons
+pub fn handle_action_requests(
+    mut action_evr: EventReader<ActionRequest>,
+    mut focus: ResMut<Focus>,
+    mut buttons: Query<&mut Interaction, With<RoundButton>>,
+) {
+    for request in action_evr.read() {
+        let NodeId(id) = request.target;
+        let entity = Entity::from_bits(id);
+        let Ok(mut interaction) = buttons.get_mut(entity) else {
+            continue;
+        };
+        match request.action {
+            Action::Default => *interaction = Interaction::Pressed,
+            Action::Focus => focus.0 = Some(entity),
+            _ => (),
+        }
+    }
+}
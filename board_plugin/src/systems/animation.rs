@@ -0,0 +1,45 @@
+use crate::{Tween, TweenEffect};
+use bevy::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+pub fn animate(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Tween, &mut Transform, Option<&mut Sprite>)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut tween, mut transform, sprite) in &mut query {
+        if tween.delay > 0. {
+            tween.delay -= delta;
+            continue;
+        }
+        tween.elapsed += delta;
+        let t = (tween.elapsed / tween.duration).min(1.);
+        // Ease out
+        let eased = 1. - (1. - t) * (1. - t);
+        let mut alpha = None;
+        match tween.effect {
+            TweenEffect::Fade => alpha = Some(1. - eased),
+            TweenEffect::Scale => transform.scale = Vec3::splat(1. - eased),
+            TweenEffect::Flip => transform.scale.x = (eased * FRAC_PI_2).cos(),
+            TweenEffect::Drop => {
+                transform.scale = Vec3::splat(1. + (1. - eased) * 0.6);
+                alpha = Some(eased);
+            }
+            TweenEffect::Explosion => {
+                transform.scale = Vec3::splat(0.5 + eased * 2.5);
+                alpha = Some(1. - t);
+            }
+        }
+        if let (Some(alpha), Some(mut sprite)) = (alpha, sprite) {
+            sprite.color.set_a(alpha);
+        }
+        if t >= 1. {
+            if tween.despawn {
+                commands.entity(entity).despawn_recursive();
+            } else {
+                commands.entity(entity).remove::<Tween>();
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::events::{Sound, SoundEvent};
+use crate::{AudioSettings, BoardAudio};
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+pub fn play_sounds(
+    mut commands: Commands,
+    audio: Option<Res<BoardAudio>>,
+    settings: Res<AudioSettings>,
+    mut sound_evr: EventReader<SoundEvent>,
+) {
+    let volume = settings.sfx_volume();
+    let Some(audio) = audio.filter(|_| volume > 0.) else {
+        // Silent, or no audio assets in headless applications
+        sound_evr.clear();
+        return;
+    };
+    for SoundEvent(sound) in sound_evr.read() {
+        let source = match sound {
+            Sound::Reveal => &audio.reveal,
+            Sound::Opening => &audio.opening,
+            Sound::Flag => &audio.flag,
+            Sound::Unflag => &audio.unflag,
+            Sound::Chord => &audio.chord,
+            Sound::Explosion => &audio.explosion,
+            Sound::Win => &audio.win,
+            Sound::ButtonHover => &audio.button_hover,
+            Sound::ButtonPress => &audio.button_press,
+        };
+        commands.spawn(AudioBundle {
+            source: source.clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+        });
+    }
+}
@@ -0,0 +1,34 @@
+use crate::bot::{is_unknown, Action};
+use crate::events::{TileMarkEvent, TileTriggerEvent};
+use crate::{Board, BotPlayer};
+use bevy::prelude::*;
+
+/// Sends the moves of the bot as clicks, once the previous move has been uncovered
+pub fn drive_bot(
+    time: Res<Time>,
+    board: Res<Board>,
+    mut bot: ResMut<BotPlayer>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+) {
+    if !bot.timer.tick(time.delta()).finished() || !board.uncovering.is_empty() {
+        return;
+    }
+    bot.timer.reset();
+    let view = board.view();
+    match bot.player.decide(&view).action {
+        Action::Reveal(coord) => {
+            tile_trigger_ewr.send(TileTriggerEvent(coord));
+        }
+        Action::Flag(coord) => {
+            tile_mark_ewr.send(TileMarkEvent(coord));
+        }
+        Action::Chord(coord) => {
+            for neighbor in view.neighbors(coord) {
+                if is_unknown(view.tile(neighbor)) {
+                    tile_trigger_ewr.send(TileTriggerEvent(neighbor));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{Board, EndlessBoard};
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Closest zoom in
+const MIN_ZOOM: f32 = 0.25;
+/// Tiles across the smallest side of the window when zoomed out, so that not too many chunks
+/// are spawned at once
+const MAX_VISIBLE_TILES: f32 = 160.;
+/// How fast the camera catches up with the reveals of the endless board, per second
+const FOLLOW_SPEED: f32 = 5.;
+
+/// Part of the world seen by the camera
+pub(crate) fn view_area(transform: &Transform, projection: &OrthographicProjection) -> Rect {
+    let center = transform.translation.truncate() + projection.area.center();
+    Rect::from_center_size(center, projection.area.size())
+}
+
+/// Centers the camera on a tile if it is out of view
+pub(crate) fn scroll_to(
+    board: &Board,
+    transform: &mut Transform,
+    projection: &OrthographicProjection,
+    coord: crate::Coordinate,
+) {
+    let tile =
+        board.bounds.position + (Vec2::new(coord.x as f32, coord.y as f32) + 0.5) * board.tile_size;
+    if !view_area(transform, projection).contains(tile) {
+        transform.translation.x = tile.x;
+        transform.translation.y = tile.y;
+    }
+}
+
+/// Puts the camera back in place for a new board
+pub fn reset_camera(
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    for (mut transform, mut projection) in &mut cameras {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+        projection.scale = 1.;
+    }
+}
+
+/// Zoom and drag requested with the mouse this frame
+fn mouse_view_input(
+    buttons: &ButtonInput<MouseButton>,
+    wheel_evr: &mut EventReader<MouseWheel>,
+    motion_evr: &mut EventReader<MouseMotion>,
+) -> (f32, Vec2) {
+    let scroll: f32 = wheel_evr
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.,
+        })
+        .sum();
+    let drag: Vec2 = motion_evr.read().map(|event| event.delta).sum();
+    if buttons.pressed(MouseButton::Middle) {
+        (scroll, drag)
+    } else {
+        (scroll, Vec2::ZERO)
+    }
+}
+
+/// Most tiles the camera can zoom out to, given the size of the window
+fn capped_zoom(window: &Window, tile_size: f32) -> f32 {
+    MAX_VISIBLE_TILES * tile_size / window.width().min(window.height())
+}
+
+fn apply_view_input(
+    transform: &mut Transform,
+    projection: &mut OrthographicProjection,
+    (scroll, drag): (f32, Vec2),
+    max_zoom: f32,
+) {
+    if scroll != 0. {
+        projection.scale = (projection.scale * 1.1f32.powf(-scroll)).clamp(MIN_ZOOM, max_zoom);
+    }
+    transform.translation.x -= drag.x * projection.scale;
+    transform.translation.y += drag.y * projection.scale;
+}
+
+/// Zooms with the mouse wheel and pans by dragging with the middle button, keeping the view over
+/// the board
+pub fn pan_and_zoom(
+    board: Res<Board>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut wheel_evr: EventReader<MouseWheel>,
+    mut motion_evr: EventReader<MouseMotion>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    let input = mouse_view_input(&buttons, &mut wheel_evr, &mut motion_evr);
+    if input == (0., Vec2::ZERO) {
+        return;
+    }
+    let (Ok(window), Ok((mut transform, mut projection))) =
+        (windows.get_single(), cameras.get_single_mut())
+    else {
+        return;
+    };
+    let fit = (board.bounds.size / Vec2::new(window.width(), window.height())).max_element();
+    let max_zoom = fit.min(capped_zoom(window, board.tile_size)).max(1.);
+    apply_view_input(&mut transform, &mut projection, input, max_zoom);
+    let (min, max) = (
+        board.bounds.position,
+        board.bounds.position + board.bounds.size,
+    );
+    transform.translation.x = transform.translation.x.clamp(min.x, max.x);
+    transform.translation.y = transform.translation.y.clamp(min.y, max.y);
+}
+
+/// Zooms and pans over the endless board like `pan_and_zoom`, without bounds. Panning stops
+/// following the reveals until the next one
+pub fn pan_and_zoom_endless(
+    mut board: ResMut<EndlessBoard>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut wheel_evr: EventReader<MouseWheel>,
+    mut motion_evr: EventReader<MouseMotion>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    let input = mouse_view_input(&buttons, &mut wheel_evr, &mut motion_evr);
+    if input == (0., Vec2::ZERO) {
+        return;
+    }
+    let (Ok(window), Ok((mut transform, mut projection))) =
+        (windows.get_single(), cameras.get_single_mut())
+    else {
+        return;
+    };
+    if input.1 != Vec2::ZERO {
+        board.focus = None;
+    }
+    let max_zoom = capped_zoom(window, board.tile_size).max(1.);
+    apply_view_input(&mut transform, &mut projection, input, max_zoom);
+}
+
+/// Moves the camera smoothly to the last reveal of the endless board
+pub fn follow_reveals(
+    time: Res<Time>,
+    mut board: ResMut<EndlessBoard>,
+    mut cameras: Query<&mut Transform, With<Camera>>,
+) {
+    let (Some(focus), Ok(mut transform)) = (board.focus, cameras.get_single_mut()) else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let step = 1. - (-FOLLOW_SPEED * time.delta_seconds()).exp();
+    let position = if position.distance(focus) < 1. {
+        board.focus = None;
+        focus
+    } else {
+        position.lerp(focus, step)
+    };
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+}
@@ -0,0 +1,259 @@
+use crate::board::Mark;
+use crate::systems::camera::view_area;
+use crate::tile::Tile;
+use crate::{
+    Board, BoardAssets, Coordinate, DroppingFlag, NumberPalette, SpectatorClient, SpriteMaterial,
+    TileAtlas,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use bevy::utils::HashSet;
+
+/// Packs the tile textures again when `BoardAssets` changes, once they are loaded
+pub fn build_tile_atlas(
+    mut commands: Commands,
+    board_assets: Res<BoardAssets>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    fonts: Res<Assets<Font>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pending: Local<bool>,
+) {
+    if board_assets.is_changed() {
+        *pending = true;
+    }
+    if !*pending {
+        return;
+    }
+    if let Some(atlas) = TileAtlas::build(
+        &board_assets,
+        &asset_server,
+        &mut images,
+        &fonts,
+        &mut materials,
+    ) {
+        commands.insert_resource(atlas);
+        *pending = false;
+    }
+}
+
+/// Spawns the chunks of tiles coming into view, despawns the ones leaving it and redraws the
+/// ones whose tiles changed, for the board and the boards of a race
+pub fn update_chunks(
+    mut commands: Commands,
+    board: Option<ResMut<Board>>,
+    mut race_boards: Query<&mut Board>,
+    mut chunk_meshes: ChunkMeshes,
+    cameras: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+) {
+    let Ok((transform, projection)) = cameras.get_single() else {
+        return;
+    };
+    let view = view_area(transform, projection);
+    for board in board
+        .map(Mut::from)
+        .into_iter()
+        .chain(race_boards.iter_mut())
+    {
+        chunk_meshes.update(&mut commands, board, view);
+    }
+}
+
+/// What the chunk meshes are drawn with
+#[derive(SystemParam)]
+pub struct ChunkMeshes<'w, 's> {
+    meshes: ResMut<'w, Assets<Mesh>>,
+    atlas: Res<'w, TileAtlas>,
+    board_assets: Res<'w, BoardAssets>,
+    palette: Res<'w, NumberPalette>,
+    spectator: Option<Res<'w, SpectatorClient>>,
+    drops: Query<'w, 's, (&'static Parent, &'static DroppingFlag)>,
+    handles: Query<'w, 's, &'static Mesh2dHandle>,
+}
+
+impl ChunkMeshes<'_, '_> {
+    fn update(&mut self, commands: &mut Commands, mut board: Mut<Board>, view: Rect) {
+        // One more tile around the view so that panning doesn't show the chunks popping in
+        let margin = Vec2::splat(board.tile_size);
+        let area = Rect::from_corners(view.min - margin, view.max + margin);
+        let visible: HashSet<(u16, u16)> = board.chunks_in(area).into_iter().collect();
+        // A new atlas replaces the material, the chunks are spawned again
+        let respawned = self.atlas.is_changed();
+        let hidden: Vec<(u16, u16)> = board
+            .chunks
+            .keys()
+            .filter(|chunk| respawned || !visible.contains(*chunk))
+            .copied()
+            .collect();
+        let shown: Vec<(u16, u16)> = visible
+            .into_iter()
+            .filter(|chunk| respawned || !board.chunks.contains_key(chunk))
+            .collect();
+        let redrawn: Vec<(u16, u16)> = if self.palette.is_changed() {
+            board.chunks.keys().copied().collect()
+        } else {
+            board.changed_chunks.iter().copied().collect()
+        };
+        // Otherwise the board is left unchanged, changing it runs the accessibility systems
+        if hidden.is_empty() && shown.is_empty() && redrawn.is_empty() {
+            return;
+        }
+        for chunk in hidden {
+            if let Some(entity) = board.chunks.remove(&chunk) {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        for chunk in redrawn {
+            let Some(handle) = board
+                .chunks
+                .get(&chunk)
+                .and_then(|entity| self.handles.get(*entity).ok())
+            else {
+                continue;
+            };
+            let mesh = self.chunk_mesh(&board, chunk);
+            self.meshes.insert(handle.0.id(), mesh);
+        }
+        board.changed_chunks.clear();
+        for chunk in shown {
+            let mesh = self.chunk_mesh(&board, chunk);
+            let entity = commands
+                .spawn(MaterialMesh2dBundle {
+                    mesh: self.meshes.add(mesh).into(),
+                    material: self.atlas.material.clone(),
+                    transform: Transform::from_xyz(0., 0., 1.),
+                    ..Default::default()
+                })
+                .insert(Name::new(format!("Chunk ({}, {})", chunk.0, chunk.1)))
+                .set_parent(board.entity)
+                .id();
+            board.chunks.insert(chunk, entity);
+        }
+    }
+
+    /// Draws the tiles of a chunk as they currently are, from the bottom layer to the top one
+    fn chunk_mesh(&self, board: &Board, chunk: (u16, u16)) -> Mesh {
+        let assets = &self.board_assets;
+        let show_mines = self.spectator.as_ref().is_some_and(|s| s.show_mines);
+        let dropping: HashSet<Coordinate> = self
+            .drops
+            .iter()
+            .filter(|(parent, _)| parent.get() == board.entity)
+            .map(|(_, drop)| drop.0)
+            .collect();
+        let size = board.tile_size - board.tile_padding;
+        let mut quads = Quads::default();
+        for coord in board.chunk_tiles(chunk) {
+            let center = board.tile_center(&coord);
+            let tile = board.tile_map.tile(coord);
+            quads.sprite(&self.atlas, &assets.tile_material, center, size);
+            if board.exploded == Some(coord) {
+                quads.sprite(&self.atlas, &assets.exploded_bomb_material, center, size);
+            }
+            match tile {
+                Tile::Bomb => quads.sprite(&self.atlas, &assets.bomb_material, center, size),
+                Tile::BombNeighbor(count) => {
+                    let color = self.palette.color(assets, count);
+                    if let Some((uv, digit)) = self.atlas.digit(count) {
+                        quads.push(center, digit * size, uv, color);
+                    }
+                    if self.palette.glyphs {
+                        let glyph_size = size * 0.35;
+                        let offset = (size - glyph_size) / 2.;
+                        quads.push(
+                            center + Vec2::new(offset, -offset),
+                            Vec2::splat(glyph_size),
+                            self.atlas.uv(&assets.number_glyph(count)),
+                            color,
+                        );
+                    }
+                }
+                _ => (),
+            }
+            if !board.states.is_covered(coord) {
+                continue;
+            }
+            // Once the game is left only the mines are revealed: correct flags stay and wrong
+            // ones are crossed out
+            let mark = board.mark_at(&coord);
+            let bomb = matches!(tile, Tile::Bomb);
+            let flagged = mark == Some(Mark::Flag);
+            let lost = board.exploded.is_some();
+            if board.covers_hidden && (!lost || (bomb && !flagged)) {
+                continue;
+            }
+            quads.sprite(&self.atlas, &assets.covered_tile_material, center, size);
+            if show_mines && bomb {
+                let material = &assets.bomb_material;
+                let uv = self.atlas.uv(&material.texture);
+                quads.push(center, Vec2::splat(size), uv, material.color.with_a(0.4));
+            }
+            let material = match mark {
+                Some(Mark::Flag) if dropping.contains(&coord) => None,
+                Some(Mark::Flag) => Some(&assets.flag_material),
+                Some(Mark::Question) => Some(&assets.question_material),
+                None => None,
+            };
+            if let Some(material) = material {
+                quads.sprite(&self.atlas, material, center, board.tile_size);
+            }
+            if board.covers_hidden && flagged && !bomb {
+                let material = &assets.wrong_flag_material;
+                quads.sprite(&self.atlas, material, center, board.tile_size);
+            }
+        }
+        quads.into_mesh()
+    }
+}
+
+/// Textured and tinted rectangles, merged into a single mesh
+#[derive(Default)]
+struct Quads {
+    positions: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl Quads {
+    fn push(&mut self, center: Vec2, size: Vec2, uv: Rect, color: Color) {
+        let (min, max) = (center - size / 2., center + size / 2.);
+        let start = self.positions.len() as u32;
+        // The textures are stored top to bottom
+        self.positions.extend([
+            [min.x, min.y, 0.],
+            [max.x, min.y, 0.],
+            [max.x, max.y, 0.],
+            [min.x, max.y, 0.],
+        ]);
+        self.uvs.extend([
+            [uv.min.x, uv.max.y],
+            [uv.max.x, uv.max.y],
+            [uv.max.x, uv.min.y],
+            [uv.min.x, uv.min.y],
+        ]);
+        self.colors.extend([color.as_linear_rgba_f32(); 4]);
+        self.indices
+            .extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+    }
+
+    /// Square of a material centered on a tile
+    fn sprite(&mut self, atlas: &TileAtlas, material: &SpriteMaterial, center: Vec2, size: f32) {
+        let uv = atlas.uv(&material.texture);
+        self.push(center, Vec2::splat(size), uv, material.color);
+    }
+
+    fn into_mesh(self) -> Mesh {
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::RENDER_WORLD,
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, self.colors)
+        .with_inserted_indices(Indices::U32(self.indices))
+    }
+}
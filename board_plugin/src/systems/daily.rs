@@ -0,0 +1,114 @@
+use crate::bot::rating;
+use crate::events::{DailyEvent, GameOverEvent, TileMarkEvent, TileTriggerEvent};
+use crate::{
+    AppState, Board, BoardAssets, BoardOptions, BoardPlugin, DailyGame, DailyHistory, DailyResult,
+    Day, ExitWindow, PuzzleGame,
+};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::time::Duration;
+
+/// Replaces the current board with the board of the day. Only the first attempt of the day is
+/// recorded
+#[allow(clippy::too_many_arguments)]
+pub fn start_daily(
+    mut commands: Commands,
+    mut daily_evr: EventReader<DailyEvent>,
+    history: Res<DailyHistory>,
+    board: Option<Res<Board>>,
+    board_options: Option<Res<BoardOptions>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    board_assets: Res<BoardAssets>,
+    exit_window: Query<Entity, With<ExitWindow>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    daily_evr.clear();
+    let day = Day::today();
+    let official = !history.results.contains_key(&day);
+    if !official {
+        log::info!(
+            "The daily board of {} was already played, this attempt is not recorded",
+            day
+        );
+    }
+    commands.remove_resource::<PuzzleGame>();
+    BoardPlugin::replace_board(
+        &mut commands,
+        board.as_deref(),
+        day.board_options(board_options.map(|o| o.clone()).unwrap_or_default()),
+        windows.get_single().ok(),
+        &board_assets,
+        &exit_window,
+        &mut next_state,
+    );
+    commands.insert_resource(DailyGame {
+        day,
+        official,
+        clicks: 0,
+        started: None,
+    });
+}
+
+/// Counts the clicks, the clock starts with the first one. The official attempt is saved as
+/// lost from then on, so leaving before the end does not allow another try
+pub fn count_daily_clicks(
+    time: Res<Time>,
+    board: Res<Board>,
+    mut daily: ResMut<DailyGame>,
+    mut history: ResMut<DailyHistory>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+) {
+    let clicks = (tile_trigger_evr.read().count() + tile_mark_evr.read().count()) as u32;
+    if clicks == 0 {
+        return;
+    }
+    daily.clicks += clicks;
+    if daily.started.is_some() {
+        return;
+    }
+    daily.started = Some(time.elapsed());
+    if daily.official {
+        let result = DailyResult {
+            won: false,
+            time: Duration::ZERO,
+            bbbv: rating::rate(&board.tile_map).bbbv,
+            clicks: daily.clicks,
+        };
+        save_result(&mut history, daily.day, result);
+    }
+}
+
+/// Records and saves the official attempt once it is over, replacing the unfinished one
+pub fn finish_daily(
+    time: Res<Time>,
+    board: Res<Board>,
+    mut daily: ResMut<DailyGame>,
+    mut history: ResMut<DailyHistory>,
+    mut game_over_evr: EventReader<GameOverEvent>,
+) {
+    for event in game_over_evr.read() {
+        if !daily.official {
+            continue;
+        }
+        daily.official = false;
+        let result = DailyResult {
+            won: *event == GameOverEvent::Won,
+            time: time.elapsed() - daily.started.unwrap_or(time.elapsed()),
+            bbbv: rating::rate(&board.tile_map).bbbv,
+            clicks: daily.clicks,
+        };
+        save_result(&mut history, daily.day, result);
+        if let Some(share) = history.share(daily.day) {
+            log::info!("{}", share);
+        }
+    }
+}
+
+fn save_result(history: &mut DailyHistory, day: Day, result: DailyResult) {
+    history.results.insert(day, result);
+    if let Err(e) = history.save() {
+        log::error!("{}", e);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::bot::rating;
+use crate::events::AnnounceEvent;
+use crate::systems::input::world_position;
+use crate::{
+    AppState, Board, BoardAssets, BoardOptions, BoardPlugin, DailyGame, EditorHud, ExitWindow,
+    Layout, LevelEditor, PuzzleGame,
+};
+use bevy::input::ButtonInput;
+use bevy::log;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+const SHORTCUTS: &str = "Click: mine | Arrows: size | C: check | S: save | P: play | Del: clear";
+
+/// Replaces the current board with the edited one
+pub fn enter_editor(
+    mut commands: Commands,
+    editor: Res<LevelEditor>,
+    board: Option<Res<Board>>,
+    board_options: Option<Res<BoardOptions>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    board_assets: Res<BoardAssets>,
+) {
+    commands.remove_resource::<DailyGame>();
+    commands.remove_resource::<PuzzleGame>();
+    respawn_board(
+        &mut commands,
+        &editor,
+        board.as_deref(),
+        board_options.map(|o| o.clone()).unwrap_or_default(),
+        windows.get_single().ok(),
+        &board_assets,
+    );
+    commands
+        .spawn(TextBundle::from_section(
+            "",
+            TextStyle {
+                font: board_assets.bomb_counter_font.clone(),
+                font_size: 20.,
+                color: Color::WHITE,
+            },
+        ))
+        .insert(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            ..default()
+        })
+        .insert(EditorHud);
+}
+
+pub fn exit_editor(mut commands: Commands, huds: Query<Entity, With<EditorHud>>) {
+    for entity in &huds {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Spawns the edited layout with every tile revealed
+fn respawn_board(
+    commands: &mut Commands,
+    editor: &LevelEditor,
+    board: Option<&Board>,
+    options: BoardOptions,
+    window: Option<&Window>,
+    board_assets: &BoardAssets,
+) {
+    let options = BoardOptions {
+        layout: Some(editor.layout.clone()),
+        start: Some(editor.start()),
+        practice_mode: false,
+        ..options
+    };
+    BoardPlugin::respawn_board(commands, board, options, window, board_assets);
+}
+
+/// Clicking a tile adds or removes its mine, the numbers around are respawned with their chunk
+pub fn edit_mines(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    input: Res<ButtonInput<MouseButton>>,
+    mut board: ResMut<Board>,
+    mut editor: ResMut<LevelEditor>,
+) {
+    if !input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let coord = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .and_then(|position| world_position(&cameras, position))
+        .and_then(|position| board.mouse_position(position));
+    let Some(coord) = coord else {
+        return;
+    };
+    let mine = board.tile_map.toggle_bomb(coord);
+    log::debug!(
+        "{} mine at {}",
+        if mine { "Added" } else { "Removed" },
+        coord
+    );
+    board.mine_changed(coord);
+    editor.layout = Layout::from_tile_map(&board.tile_map);
+    editor.status = format!("{} mines", editor.layout.mines.len());
+}
+
+/// Resizes, clears, checks, saves or plays the edited layout, escape goes back to the menu
+#[allow(clippy::too_many_arguments)]
+pub fn handle_editor_keys(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<LevelEditor>,
+    board: Option<Res<Board>>,
+    board_options: Option<Res<BoardOptions>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    board_assets: Res<BoardAssets>,
+    exit_window: Query<Entity, With<ExitWindow>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut announce_ewr: EventWriter<AnnounceEvent>,
+) {
+    let options = board_options.map(|o| o.clone()).unwrap_or_default();
+    let window = windows.get_single().ok();
+    let resize = [
+        (KeyCode::ArrowLeft, (-1, 0)),
+        (KeyCode::ArrowRight, (1, 0)),
+        (KeyCode::ArrowDown, (0, -1)),
+        (KeyCode::ArrowUp, (0, 1)),
+    ]
+    .into_iter()
+    .find(|(key, _)| keys.just_pressed(*key));
+    if let Some((_, (dx, dy))) = resize {
+        editor.resize(dx, dy);
+        editor.status = format!("{}x{} board", editor.layout.width, editor.layout.height);
+        respawn_board(
+            &mut commands,
+            &editor,
+            board.as_deref(),
+            options,
+            window,
+            &board_assets,
+        );
+    } else if keys.any_just_pressed([KeyCode::Delete, KeyCode::Backspace]) {
+        editor.layout.mines.clear();
+        editor.status = "Cleared".into();
+        respawn_board(
+            &mut commands,
+            &editor,
+            board.as_deref(),
+            options,
+            window,
+            &board_assets,
+        );
+    } else if keys.just_pressed(KeyCode::KeyC) {
+        let Some(board) = &board else {
+            return;
+        };
+        let rating = rating::rate(&board.tile_map);
+        let verdict = match rating.forced_guesses {
+            0 => "Solvable without guessing".to_string(),
+            1 => "Needs a guess".to_string(),
+            n => format!("Needs {n} guesses"),
+        };
+        editor.status = format!("{verdict}: {rating}");
+    } else if keys.just_pressed(KeyCode::KeyS) {
+        editor.status = match editor.layout.write(&editor.path) {
+            Ok(()) => format!("Saved to {}", editor.path.display()),
+            Err(e) => e.to_string(),
+        };
+    } else if keys.just_pressed(KeyCode::KeyP) {
+        BoardPlugin::replace_board(
+            &mut commands,
+            board.as_deref(),
+            BoardOptions {
+                layout: Some(editor.layout.clone()),
+                start: None,
+                ..options
+            },
+            window,
+            &board_assets,
+            &exit_window,
+            &mut next_state,
+        );
+        return;
+    } else if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::Out);
+        return;
+    } else {
+        return;
+    }
+    log::info!("{}", editor.status);
+    announce_ewr.send(AnnounceEvent(editor.status.clone()));
+}
+
+pub fn update_editor_hud(editor: Res<LevelEditor>, mut huds: Query<&mut Text, With<EditorHud>>) {
+    for mut text in &mut huds {
+        text.sections[0].value = format!("{SHORTCUTS}\n{}", editor.status);
+    }
+}
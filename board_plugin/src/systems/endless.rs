@@ -0,0 +1,339 @@
+use crate::button_style::ExitWindowTitle;
+use crate::endless::{EndlessGame, EndlessState};
+use crate::events::{EndlessMarkEvent, EndlessTriggerEvent, NewGameEvent, Sound, SoundEvent};
+use crate::systems::camera::view_area;
+use crate::systems::mark::{refresh_mark, spawn_mark};
+use crate::systems::uncover::{cover_tween, spawn_exploded_bomb, spawn_explosion};
+use crate::{
+    AnimationOptions, AppState, BoardAssets, BoardOptions, BoardPlugin, EndlessBoard,
+    EndlessOptions, ExitWindow, Mark, NumberPalette, ScoreText, TileSize,
+};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy::window::PrimaryWindow;
+
+/// Starts an endless game unless one was left for the menu
+pub fn create_endless_board(
+    mut commands: Commands,
+    board: Option<Res<EndlessBoard>>,
+    options: Res<EndlessOptions>,
+    board_options: Option<Res<BoardOptions>>,
+    board_assets: Res<BoardAssets>,
+    scores: Query<(), With<ScoreText>>,
+) {
+    if scores.is_empty() {
+        commands
+            .spawn(score_bundle(&board_assets))
+            .insert(ScoreText);
+    }
+    if board.is_some() {
+        return;
+    }
+    let board_options = board_options.map(|o| o.clone()).unwrap_or_default();
+    let board = spawn_endless_board(&mut commands, &options, &board_options);
+    commands.insert_resource(board);
+}
+
+/// Replaces the endless board with a new world, whatever the state
+#[allow(clippy::too_many_arguments)]
+pub fn new_endless_game(
+    mut commands: Commands,
+    mut new_game_evr: EventReader<NewGameEvent>,
+    board: Option<Res<EndlessBoard>>,
+    options: Res<EndlessOptions>,
+    board_options: Option<Res<BoardOptions>>,
+    exit_window: Query<Entity, With<ExitWindow>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    new_game_evr.clear();
+    if let Some(board) = board {
+        commands.entity(board.entity).despawn_recursive();
+        // Removed first so the new board counts as added
+        commands.remove_resource::<EndlessBoard>();
+    }
+    for entity in &exit_window {
+        commands.entity(entity).despawn_recursive();
+    }
+    let board_options = board_options.map(|o| o.clone()).unwrap_or_default();
+    let board = spawn_endless_board(&mut commands, &options, &board_options);
+    commands.insert_resource(board);
+    next_state.set(AppState::InGame);
+}
+
+/// Spawns the board entity, the tiles are spawned by chunks once in view. Adaptive tiles get
+/// their maximum size
+fn spawn_endless_board(
+    commands: &mut Commands,
+    options: &EndlessOptions,
+    board_options: &BoardOptions,
+) -> EndlessBoard {
+    let seed = options.seed.unwrap_or_else(rand::random);
+    log::info!("Endless world seed: {}", seed);
+    let tile_size = match board_options.tile_size {
+        TileSize::Fixed(v) => v,
+        TileSize::Adaptive { max, .. } => max,
+    };
+    let entity = commands
+        .spawn(SpatialBundle::default())
+        .insert(Name::new("Endless Board"))
+        .id();
+    EndlessBoard {
+        game: EndlessGame::new(seed, options.bombs_per_chunk),
+        tile_size,
+        tile_padding: board_options.tile_padding,
+        tiles: Default::default(),
+        covers: Default::default(),
+        chunks: Default::default(),
+        focus: None,
+        entity,
+    }
+}
+
+fn score_bundle(board_assets: &BoardAssets) -> TextBundle {
+    TextBundle::from_section(
+        "",
+        TextStyle {
+            font: board_assets.bomb_counter_font.clone(),
+            font_size: 30.,
+            color: Color::WHITE,
+        },
+    )
+    .with_style(Style {
+        position_type: PositionType::Absolute,
+        top: Val::Px(10.),
+        left: Val::Px(10.),
+        ..default()
+    })
+}
+
+/// Shows the tiles cleared so far
+pub fn update_score(board: Res<EndlessBoard>, mut scores: Query<&mut Text, With<ScoreText>>) {
+    for mut text in &mut scores {
+        text.sections[0].value = format!("Cleared: {}", board.game.cleared());
+    }
+}
+
+/// Left click reveals a tile of the endless board, right click flags it
+pub fn handle_endless_mouse_input(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    board: Res<EndlessBoard>,
+    input: Res<ButtonInput<MouseButton>>,
+    mut trigger_ewr: EventWriter<EndlessTriggerEvent>,
+    mut mark_ewr: EventWriter<EndlessMarkEvent>,
+) {
+    let (Ok(window), Ok((camera, transform))) = (windows.get_single(), cameras.get_single()) else {
+        return;
+    };
+    let Some(position) = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world_2d(transform, position))
+    else {
+        return;
+    };
+    let coordinate = board.mouse_position(position);
+    if input.just_pressed(MouseButton::Left) {
+        trigger_ewr.send(EndlessTriggerEvent(coordinate));
+    } else if input.just_pressed(MouseButton::Right) {
+        mark_ewr.send(EndlessMarkEvent(coordinate));
+    }
+}
+
+/// Reveals the clicked tiles and their openings, and ends the game on a mine
+#[allow(clippy::too_many_arguments)]
+pub fn reveal_endless_tiles(
+    mut commands: Commands,
+    mut board: ResMut<EndlessBoard>,
+    board_assets: Res<BoardAssets>,
+    animations: Option<Res<AnimationOptions>>,
+    mut trigger_evr: EventReader<EndlessTriggerEvent>,
+    mut exit_window_title: ResMut<ExitWindowTitle>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+) {
+    let animations = animations.filter(|a| a.enabled);
+    for EndlessTriggerEvent(origin) in trigger_evr.read() {
+        let revealed = board.game.reveal(*origin);
+        if revealed.is_empty() {
+            continue;
+        }
+        log::debug!("Uncovered {} tiles from {}", revealed.len(), origin);
+        for coord in &revealed {
+            let Some(cover) = board.covers.remove(coord) else {
+                continue;
+            };
+            match &animations {
+                None => commands.entity(cover).despawn_recursive(),
+                Some(animations) => {
+                    let distance =
+                        Vec2::new((coord.x - origin.x) as f32, (coord.y - origin.y) as f32)
+                            .length();
+                    commands
+                        .entity(cover)
+                        .despawn_descendants()
+                        .insert(cover_tween(animations, distance));
+                }
+            }
+        }
+        // The camera heads to the middle of the opening
+        let center = revealed
+            .iter()
+            .map(|coord| board.tile_position(*coord))
+            .sum::<Vec2>()
+            / revealed.len() as f32;
+        board.focus = Some(center);
+
+        if let EndlessState::Lost(mine) = board.game.state() {
+            log::info!("Boom ! {} tiles cleared", board.game.cleared());
+            sound_ewr.send(SoundEvent(Sound::Explosion));
+            if let Some(tile) = board.tiles.get(&mine) {
+                let size = Some(Vec2::splat(board.tile_size - board.tile_padding));
+                let explosion = animations.as_ref().is_some_and(|a| a.explosion);
+                commands.entity(*tile).with_children(|parent| {
+                    spawn_explosion(parent, &board_assets, size, explosion);
+                });
+            }
+            exit_window_title.text = format!("{} TILES CLEARED", board.game.cleared());
+            next_state.set(AppState::Out);
+            break;
+        }
+        sound_ewr.send(SoundEvent(if revealed.len() > 1 {
+            Sound::Opening
+        } else {
+            Sound::Reveal
+        }));
+    }
+}
+
+/// Flags or unflags the right clicked tiles
+pub fn mark_endless_tiles(
+    mut commands: Commands,
+    mut board: ResMut<EndlessBoard>,
+    board_assets: Res<BoardAssets>,
+    mut mark_evr: EventReader<EndlessMarkEvent>,
+    children: Query<&Children>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+) {
+    for EndlessMarkEvent(coord) in mark_evr.read() {
+        let Some(flagged) = board.game.toggle_flag(*coord) else {
+            continue;
+        };
+        sound_ewr.send(SoundEvent(if flagged {
+            Sound::Flag
+        } else {
+            Sound::Unflag
+        }));
+        if let Some(cover) = board.covers.get(coord) {
+            refresh_mark(
+                &mut commands,
+                &children,
+                *cover,
+                flagged.then_some(Mark::Flag),
+                &board_assets,
+                board.tile_size,
+            );
+        }
+    }
+}
+
+/// Spawns the chunks of the endless board coming into view and despawns the ones leaving it
+pub fn update_endless_chunks(
+    mut commands: Commands,
+    mut board: ResMut<EndlessBoard>,
+    board_assets: Res<BoardAssets>,
+    palette: Res<NumberPalette>,
+    cameras: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+) {
+    let Ok((transform, projection)) = cameras.get_single() else {
+        return;
+    };
+    // One more tile around the view so that panning doesn't show the chunks popping in
+    let view = view_area(transform, projection);
+    let margin = Vec2::splat(board.tile_size);
+    let area = Rect::from_corners(view.min - margin, view.max + margin);
+    let visible: HashSet<(i32, i32)> = board.chunks_in(area).into_iter().collect();
+    let hidden: Vec<(i32, i32)> = board
+        .chunks
+        .keys()
+        .filter(|chunk| !visible.contains(*chunk))
+        .copied()
+        .collect();
+    let shown: Vec<(i32, i32)> = visible
+        .into_iter()
+        .filter(|chunk| !board.chunks.contains_key(chunk))
+        .collect();
+    if hidden.is_empty() && shown.is_empty() {
+        return;
+    }
+    for chunk in hidden {
+        despawn_chunk(&mut commands, &mut board, chunk);
+    }
+    for chunk in shown {
+        spawn_chunk(&mut commands, &mut board, chunk, &board_assets, &palette);
+    }
+}
+
+/// Spawns the tiles of a chunk as they currently are, generating its mines if needed
+fn spawn_chunk(
+    commands: &mut Commands,
+    board: &mut EndlessBoard,
+    chunk: (i32, i32),
+    board_assets: &BoardAssets,
+    palette: &NumberPalette,
+) {
+    let tile_size = board.tile_size;
+    let chunk_entity = commands
+        .spawn(SpatialBundle::default())
+        .insert(Name::new(format!("Chunk ({}, {})", chunk.0, chunk.1)))
+        .set_parent(board.entity)
+        .id();
+    let lost = match board.game.state() {
+        EndlessState::Lost(mine) => Some(mine),
+        EndlessState::Playing => None,
+    };
+    for coordinate in EndlessBoard::chunk_tiles(chunk) {
+        let tile = BoardPlugin::spawn_tile(
+            commands,
+            board.game.tile(coordinate),
+            board.tile_position(coordinate),
+            tile_size,
+            board.tile_padding,
+            board_assets,
+            palette,
+        );
+        commands
+            .entity(tile)
+            .insert(Name::new(format!("Tile {}", coordinate)))
+            .insert(coordinate)
+            .set_parent(chunk_entity);
+        board.tiles.insert(coordinate, tile);
+        if lost == Some(coordinate) {
+            let size = Some(Vec2::splat(tile_size - board.tile_padding));
+            commands.entity(tile).with_children(|parent| {
+                spawn_exploded_bomb(parent, board_assets, size);
+            });
+        }
+        if !board.game.is_covered(coordinate) {
+            continue;
+        }
+        let cover =
+            BoardPlugin::spawn_cover(commands, tile, tile_size - board.tile_padding, board_assets);
+        if board.game.is_flagged(coordinate) {
+            spawn_mark(commands, cover, Some(Mark::Flag), board_assets, tile_size);
+        }
+        board.covers.insert(coordinate, cover);
+    }
+    board.chunks.insert(chunk, chunk_entity);
+}
+
+fn despawn_chunk(commands: &mut Commands, board: &mut EndlessBoard, chunk: (i32, i32)) {
+    let Some(entity) = board.chunks.remove(&chunk) else {
+        return;
+    };
+    commands.entity(entity).despawn_recursive();
+    for coordinate in EndlessBoard::chunk_tiles(chunk) {
+        board.tiles.remove(&coordinate);
+        board.covers.remove(&coordinate);
+    }
+}
@@ -1,9 +1,13 @@
 use crate::button_style::ButtonStyle;
 use crate::button_style::ExitWindowTitle;
+use crate::events::{DailyEvent, NewGameEvent, Sound, SoundEvent};
 use crate::AppState;
-use crate::Board;
 use crate::ExitWindow;
 use crate::RoundButton;
+use crate::{DailyGame, DailyHistory, LevelEditor, PuzzlePacks};
+use crate::{ExitPanel, ExitPanelToggle};
+use bevy::a11y::accesskit::{NodeBuilder, Role};
+use bevy::a11y::AccessibilityNode;
 use bevy::{app::AppExit, prelude::*};
 use bevy_round_ui::{autosize::*, prelude::*};
 
@@ -12,51 +16,116 @@ use bevy::input::ButtonInput;
 #[derive(Component, Debug)]
 pub enum ButtonAction {
     Play,
+    Daily,
+    Puzzles,
+    Editor,
+    ViewBoard,
+    ShowMenu,
+    OpenSettings,
     Quit,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn setup_exit_window(
     mut commands: Commands,
     button_style: Res<ButtonStyle>,
     title: Res<ExitWindowTitle>,
+    daily_history: Option<Res<DailyHistory>>,
+    daily: Option<Res<DailyGame>>,
+    puzzles: Option<Res<PuzzlePacks>>,
+    editor: Option<Res<LevelEditor>>,
     mut materials: ResMut<Assets<RoundUiMaterial>>,
 ) {
+    // The result of the daily board and the streak calendar are shown once it is played
+    let daily_summary = daily.zip(daily_history.as_deref()).map(|(daily, history)| {
+        let result = history
+            .share(daily.day)
+            .unwrap_or_else(|| format!("Minesweeper daily {}", daily.day));
+        format!("{result}\n\n{}", history.calendar(daily.day))
+    });
     // Define a material for the panel.
     // This material looks like it has a border, because we applied an equal offset to all sides.
-    let panel_width = 300.0;
-    let panel_height = 250.0;
+    let panel_width = if daily_summary.is_some() {
+        400.0
+    } else {
+        300.0
+    };
+    let mut panel_height = 350.0;
+    if daily_history.is_some() {
+        panel_height += 60.;
+    }
+    if puzzles.is_some() {
+        panel_height += 60.;
+    }
+    if editor.is_some() {
+        panel_height += 60.;
+    }
+    if daily_summary.is_some() {
+        panel_height += 180.;
+    }
     let panel_material = materials.add(RoundUiMaterial {
-        background_color: Color::hex("5cb3af").unwrap(),
-        border_color: Color::WHITE,
+        background_color: button_style.panel_background,
+        border_color: button_style.panel_border,
         border_radius: RoundUiBorder::all(20.0).into(),
         size: Vec2::new(panel_width, panel_height),
         offset: RoundUiOffset::all(6.0).into(),
     });
 
-    // Spawn the screen layout, containing a centered panel with menu items
+    // Spawn the screen layout, containing a panel with menu items docked to the top right
+    // corner so that the finished board stays visible
     commands
         .spawn(NodeBundle {
             style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.),
+                right: Val::Px(10.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
                 ..default()
             },
             ..default()
         })
         .with_children(|p| {
+            let mut dialog = NodeBuilder::new(Role::Dialog);
+            dialog.set_name(title.text.clone());
             p.spawn(panel_bundle(panel_material, panel_width, panel_height))
+                .insert(ExitPanel)
+                .insert(AccessibilityNode(dialog))
                 .with_children(|p| {
-                    spawn_title(p, &title);
+                    spawn_title(p, &title.text);
+                    if let Some(summary) = &daily_summary {
+                        spawn_daily_summary(p, summary);
+                    }
                     spawn_button(p, &button_style, "New Game", ButtonAction::Play);
+                    if daily_history.is_some() {
+                        spawn_button(p, &button_style, "Daily", ButtonAction::Daily);
+                    }
+                    if puzzles.is_some() {
+                        spawn_button(p, &button_style, "Puzzles", ButtonAction::Puzzles);
+                    }
+                    if editor.is_some() {
+                        spawn_button(p, &button_style, "Editor", ButtonAction::Editor);
+                    }
+                    spawn_button(p, &button_style, "View Board", ButtonAction::ViewBoard);
+                    spawn_button(p, &button_style, "Settings", ButtonAction::OpenSettings);
                     spawn_button(p, &button_style, "Quit", ButtonAction::Quit);
                 });
+            p.spawn(NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(ExitPanelToggle)
+            .with_children(|p| {
+                spawn_button(p, &button_style, "Show Menu", ButtonAction::ShowMenu);
+            });
         })
         .insert(ExitWindow);
 }
 
-fn spawn_title(parent: &mut ChildBuilder, title: &Res<ExitWindowTitle>) {
+pub(crate) fn spawn_title(parent: &mut ChildBuilder, title: &str) {
     parent
         .spawn(NodeBundle {
             style: Style {
@@ -69,7 +138,7 @@ fn spawn_title(parent: &mut ChildBuilder, title: &Res<ExitWindowTitle>) {
         })
         .with_children(|parent| {
             parent.spawn(TextBundle::from_section(
-                title.text.clone(),
+                title,
                 TextStyle {
                     color: Color::WHITE,
                     font_size: 40.,
@@ -79,7 +148,32 @@ fn spawn_title(parent: &mut ChildBuilder, title: &Res<ExitWindowTitle>) {
         });
 }
 
-fn panel_bundle(
+fn spawn_daily_summary(parent: &mut ChildBuilder, summary: &str) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                max_width: Val::Px(360.),
+                margin: UiRect::bottom(Val::Px(20.)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    summary,
+                    TextStyle {
+                        color: Color::WHITE,
+                        font_size: 18.,
+                        ..default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+            );
+        });
+}
+
+pub(crate) fn panel_bundle(
     panel_material: Handle<RoundUiMaterial>,
     panel_width: f32,
     panel_height: f32,
@@ -98,12 +192,15 @@ fn panel_bundle(
     }
 }
 
-fn spawn_button(
+pub(crate) fn spawn_button(
     parent: &mut ChildBuilder,
     button_style: &Res<ButtonStyle>,
     text: impl Into<String>,
     extras: impl Bundle,
 ) -> Entity {
+    let text = text.into();
+    let mut node = NodeBuilder::new(Role::Button);
+    node.set_name(text.clone());
     parent
         .spawn((
             RoundButton,
@@ -123,6 +220,7 @@ fn spawn_button(
             },
             extras,
             Interaction::default(),
+            AccessibilityNode(node),
         ))
         .with_children(|p| {
             p.spawn(TextBundle::from_section(
@@ -144,21 +242,32 @@ pub fn handle_button_interactions(
         (Changed<Interaction>, With<RoundButton>),
     >,
     button_style: Res<ButtonStyle>,
+    mut sound_ewr: EventWriter<SoundEvent>,
 ) {
     for (interaction, mut material) in &mut interaction_query {
         *material = match *interaction {
-            Interaction::Pressed => button_style.press.clone(),
-            Interaction::Hovered => button_style.hover.clone(),
+            Interaction::Pressed => {
+                sound_ewr.send(SoundEvent(Sound::ButtonPress));
+                button_style.press.clone()
+            }
+            Interaction::Hovered => {
+                sound_ewr.send(SoundEvent(Sound::ButtonHover));
+                button_style.hover.clone()
+            }
             Interaction::None => button_style.default.clone(),
         };
     }
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_button_actions(
     mut commands: Commands,
     interaction_query: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
     exit_window: Query<Entity, With<ExitWindow>>,
-    board: Res<Board>,
+    mut panel: Query<&mut Style, With<ExitPanel>>,
+    mut toggle: Query<&mut Style, (With<ExitPanelToggle>, Without<ExitPanel>)>,
+    mut new_game_ewr: EventWriter<NewGameEvent>,
+    mut daily_ewr: EventWriter<DailyEvent>,
     mut next_state: ResMut<NextState<AppState>>,
     mut app_exit_events: EventWriter<AppExit>,
     mut mouse_input: ResMut<ButtonInput<MouseButton>>,
@@ -168,13 +277,34 @@ pub fn handle_button_actions(
             println!("Button pressed: {action:?}");
             match action {
                 ButtonAction::Play => {
+                    mouse_input.clear();
+                    new_game_ewr.send(NewGameEvent);
+                }
+                ButtonAction::Daily => {
+                    mouse_input.clear();
+                    daily_ewr.send(DailyEvent);
+                }
+                ButtonAction::ViewBoard => collapse_panel(true, &mut panel, &mut toggle),
+                ButtonAction::ShowMenu => collapse_panel(false, &mut panel, &mut toggle),
+                ButtonAction::Puzzles => {
                     commands
                         .entity(exit_window.iter().next().unwrap())
                         .despawn_recursive();
-                    commands.entity(board.entity).despawn_recursive();
-                    commands.remove_resource::<Board>();
+                    next_state.set(AppState::Puzzles);
+                }
+                ButtonAction::Editor => {
+                    // Keep the click from also placing a mine
                     mouse_input.clear();
-                    next_state.set(AppState::InGame);
+                    commands
+                        .entity(exit_window.iter().next().unwrap())
+                        .despawn_recursive();
+                    next_state.set(AppState::Editor);
+                }
+                ButtonAction::OpenSettings => {
+                    commands
+                        .entity(exit_window.iter().next().unwrap())
+                        .despawn_recursive();
+                    next_state.set(AppState::Settings);
                 }
                 ButtonAction::Quit => {
                     app_exit_events.send(AppExit);
@@ -183,3 +313,34 @@ pub fn handle_button_actions(
         }
     }
 }
+
+#[allow(clippy::type_complexity)]
+pub fn reopen_exit_window(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut panel: Query<&mut Style, With<ExitPanel>>,
+    mut toggle: Query<&mut Style, (With<ExitPanelToggle>, Without<ExitPanel>)>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        collapse_panel(false, &mut panel, &mut toggle);
+    }
+}
+
+/// Swaps the exit panel with a single button so the final board can be reviewed
+#[allow(clippy::type_complexity)]
+fn collapse_panel(
+    collapsed: bool,
+    panel: &mut Query<&mut Style, With<ExitPanel>>,
+    toggle: &mut Query<&mut Style, (With<ExitPanelToggle>, Without<ExitPanel>)>,
+) {
+    let (panel_display, toggle_display) = if collapsed {
+        (Display::None, Display::Flex)
+    } else {
+        (Display::Flex, Display::None)
+    };
+    for mut style in panel.iter_mut() {
+        style.display = panel_display;
+    }
+    for mut style in toggle.iter_mut() {
+        style.display = toggle_display;
+    }
+}
@@ -0,0 +1,93 @@
+use crate::events::{HistoryEvent, MovePlayedEvent};
+use crate::history::Move;
+use crate::{AppState, Board, Coordinate, ExitWindow, LossIndicator, ReplayAction};
+use bevy::log;
+use bevy::prelude::*;
+
+#[allow(clippy::too_many_arguments)]
+pub fn undo_redo(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    mut history_evr: EventReader<HistoryEvent>,
+    exit_window: Query<Entity, With<ExitWindow>>,
+    loss_indicators: Query<Entity, With<LossIndicator>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut move_played_ewr: EventWriter<MovePlayedEvent>,
+) {
+    for event in history_evr.read() {
+        move_played_ewr.send(MovePlayedEvent(match event {
+            HistoryEvent::Undo => ReplayAction::Undo,
+            HistoryEvent::Redo => ReplayAction::Redo,
+        }));
+        let in_game = *state.get() == AppState::InGame;
+        match event {
+            HistoryEvent::Undo => {
+                let Some(m) = board.history.undo() else {
+                    continue;
+                };
+                log::info!("Undo {:?}", m);
+                board.practice = true;
+                undo_move(&mut board, m);
+                if !in_game {
+                    // Taking back the last move of a finished game resumes it
+                    for entity in exit_window.iter().chain(loss_indicators.iter()) {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                    board.exploded = None;
+                    board.covers_hidden = false;
+                    board.all_tiles_changed();
+                    next_state.set(AppState::InGame);
+                }
+            }
+            HistoryEvent::Redo => {
+                if !in_game {
+                    continue;
+                }
+                let Some(m) = board.history.redo() else {
+                    continue;
+                };
+                log::info!("Redo {:?}", m);
+                redo_move(&mut board, m);
+            }
+        }
+    }
+}
+
+fn undo_move(board: &mut Board, m: Move) {
+    match m {
+        Move::Reveal(revealed) => {
+            for (coord, mark) in revealed {
+                board.restore_cover(coord, mark);
+            }
+        }
+        Move::Mark { coord, from, .. } => {
+            set_mark(board, coord, from);
+        }
+    }
+}
+
+fn redo_move(board: &mut Board, m: Move) {
+    match m {
+        Move::Reveal(revealed) => {
+            // The tiles are uncovered again by `uncover_tiles` which records them back
+            if let Some((origin, _)) = revealed.first() {
+                board.reveal_origin = *origin;
+            }
+            board
+                .uncovering
+                .extend(revealed.into_iter().map(|(coord, _)| coord));
+        }
+        Move::Mark { coord, to, .. } => {
+            set_mark(board, coord, to);
+        }
+    }
+}
+
+fn set_mark(board: &mut Board, coord: Coordinate, mark: Option<crate::board::Mark>) {
+    if !board.states.is_covered(coord) {
+        log::error!("Failed to mark uncovered tile {}", coord);
+        return;
+    }
+    board.set_mark(&coord, mark);
+}
@@ -1,12 +1,23 @@
-use crate::events::{TileMarkEvent, TileTriggerEvent};
-use crate::Board;
+use crate::events::{HistoryEvent, TileMarkEvent, TileTriggerEvent};
+use crate::systems::camera::scroll_to;
+use crate::{Board, KeyBindings};
 use bevy::input::ButtonInput;
 use bevy::log;
 use bevy::prelude::*;
 use bevy::window::{PrimaryWindow, Window};
 
+/// Position in the world of a point of the window
+pub(crate) fn world_position(
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+    position: Vec2,
+) -> Option<Vec2> {
+    let (camera, transform) = cameras.get_single().ok()?;
+    camera.viewport_to_world_2d(transform, position)
+}
+
 pub fn handle_mouse_input(
     windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
     board: Res<Board>,
     input: Res<ButtonInput<MouseButton>>,
     mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
@@ -21,7 +32,8 @@ pub fn handle_mouse_input(
         let position = window.cursor_position();
         if let Some(pos) = position {
             log::trace!("Mouse button pressed: left at {}", pos);
-            let tile_coordinate = board.mouse_position(window, pos);
+            let tile_coordinate =
+                world_position(&cameras, pos).and_then(|pos| board.mouse_position(pos));
             if let Some(coordinate) = tile_coordinate {
                 tile_trigger_ewr.send(TileTriggerEvent(coordinate));
             }
@@ -30,10 +42,78 @@ pub fn handle_mouse_input(
         let position = window.cursor_position();
         if let Some(pos) = position {
             log::trace!("Mouse button pressed: right at {}", pos);
-            let tile_coordinate = board.mouse_position(window, pos);
+            let tile_coordinate =
+                world_position(&cameras, pos).and_then(|pos| board.mouse_position(pos));
             if let Some(coordinate) = tile_coordinate {
                 tile_mark_ewr.send(TileMarkEvent(coordinate));
             }
         }
     }
 }
+
+pub fn handle_keyboard_input(
+    board: Res<Board>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut history_ewr: EventWriter<HistoryEvent>,
+) {
+    if !board.practice_mode {
+        return;
+    }
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !ctrl {
+        return;
+    }
+    if keys.just_pressed(bindings.redo) || (shift && keys.just_pressed(bindings.undo)) {
+        log::trace!("Redo requested");
+        history_ewr.send(HistoryEvent::Redo);
+    } else if keys.just_pressed(bindings.undo) {
+        log::trace!("Undo requested");
+        history_ewr.send(HistoryEvent::Undo);
+    }
+}
+
+/// Moves the board cursor with the keyboard or the mouse, and reveals or flags the cursor tile.
+/// The view follows the cursor moved with the keyboard
+#[allow(clippy::too_many_arguments)]
+pub fn handle_cursor_input(
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut views: Query<(&mut Transform, &OrthographicProjection), With<Camera>>,
+    mut board: ResMut<Board>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut cursor_evr: EventReader<CursorMoved>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+) {
+    if let Some(event) = cursor_evr.read().last() {
+        let position = world_position(&cameras, event.position);
+        if let Some(coordinate) = position.and_then(|pos| board.mouse_position(pos)) {
+            if board.cursor != coordinate {
+                board.cursor = coordinate;
+            }
+        }
+    }
+    let moves = [
+        (bindings.up, (0, 1)),
+        (bindings.down, (0, -1)),
+        (bindings.left, (-1, 0)),
+        (bindings.right, (1, 0)),
+    ];
+    for (key, (dx, dy)) in moves {
+        if keys.just_pressed(key) {
+            board.move_cursor(dx, dy);
+            if let Ok((mut transform, projection)) = views.get_single_mut() {
+                scroll_to(&board, &mut transform, projection, board.cursor);
+            }
+        }
+    }
+    if keys.just_pressed(bindings.reveal) {
+        log::trace!("Keyboard reveal at {}", board.cursor);
+        tile_trigger_ewr.send(TileTriggerEvent(board.cursor));
+    } else if keys.just_pressed(bindings.flag) {
+        log::trace!("Keyboard mark at {}", board.cursor);
+        tile_mark_ewr.send(TileMarkEvent(board.cursor));
+    }
+}
@@ -1,43 +1,136 @@
-use crate::{Board, BoardAssets, TileMarkEvent};
+use crate::board::Mark;
+use crate::events::{MovePlayedEvent, Sound, SoundEvent};
+use crate::{
+    AnimationOptions, Board, BoardAssets, BoardSprite, Coordinate, DroppingFlag, ReplayAction,
+    TileMarkEvent, Tween, TweenEffect,
+};
 use bevy::log;
 use bevy::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn mark_tiles(
     mut commands: Commands,
     mut board: ResMut<Board>,
     board_assets: Res<BoardAssets>,
+    animations: Option<Res<AnimationOptions>>,
     mut tile_mark_evr: EventReader<TileMarkEvent>,
-    query: Query<&Children>,
+    drops: Query<(Entity, &DroppingFlag)>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+    mut move_played_ewr: EventWriter<MovePlayedEvent>,
 ) {
+    let flag_drop = animations.is_some_and(|a| a.enabled && a.flag_drop);
     for event in tile_mark_evr.read() {
-        if let Some((entity, mark)) = board.try_toggle_mark(&event.0) {
-            if mark {
-                commands.entity(entity).with_children(|parent| {
-                    parent
-                        .spawn(SpriteBundle {
-                            sprite: Sprite {
-                                custom_size: Some(Vec2::splat(board.tile_size)),
-                                color: board_assets.flag_material.color,
-                                ..Default::default()
-                            },
-                            texture: board_assets.flag_material.texture.clone(),
-                            transform: Transform::from_xyz(0., 0., 1.),
-                            ..Default::default()
-                        })
-                        .insert(Name::new("Flag"));
-                });
+        move_played_ewr.send(MovePlayedEvent(ReplayAction::Mark(event.0)));
+        if let Some(mark) = board.try_toggle_mark(&event.0) {
+            log::debug!("Marked tile {} as {:?}", event.0, mark);
+            sound_ewr.send(SoundEvent(if mark == Some(Mark::Flag) {
+                Sound::Flag
             } else {
-                let children = match query.get(entity) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        log::error!("Failed to retrieve flag entity components: {}", e);
-                        continue;
-                    }
-                };
-                for child in children.iter() {
-                    commands.entity(*child).despawn_recursive();
+                Sound::Unflag
+            }));
+            // A flag removed while dropping doesn't land
+            for (entity, drop) in &drops {
+                if drop.0 == event.0 {
+                    commands.entity(entity).despawn_recursive();
                 }
             }
+            // Off-screen tiles are not animated
+            if flag_drop && mark == Some(Mark::Flag) && board.is_spawned(&event.0) {
+                spawn_dropping_flag(&mut commands, &board, &board_assets, event.0);
+            }
+        }
+    }
+}
+
+/// Drops a flag onto its tile, its chunk draws it once it landed
+fn spawn_dropping_flag(
+    commands: &mut Commands,
+    board: &Board,
+    board_assets: &BoardAssets,
+    coord: Coordinate,
+) {
+    let material = &board_assets.flag_material;
+    commands.entity(board.entity).with_children(|parent| {
+        parent
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(board.tile_size)),
+                    color: material.color,
+                    ..Default::default()
+                },
+                texture: material.texture.clone(),
+                transform: Transform::from_translation(board.tile_center(&coord).extend(3.)),
+                ..Default::default()
+            })
+            .insert(Name::new("Flag"))
+            .insert(DroppingFlag(coord))
+            .insert(Tween::new(TweenEffect::Drop, 0.15));
+    });
+}
+
+/// Hands the flags that landed over to their chunk
+pub fn land_flags(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    drops: Query<(Entity, &DroppingFlag), Without<Tween>>,
+) {
+    for (entity, drop) in &drops {
+        commands.entity(entity).despawn_recursive();
+        board.tile_changed(drop.0);
+    }
+}
+
+/// Replaces the mark sprite displayed on a tile cover, returning the new sprite if any
+pub(crate) fn refresh_mark(
+    commands: &mut Commands,
+    query: &Query<&Children>,
+    cover: Entity,
+    mark: Option<Mark>,
+    board_assets: &BoardAssets,
+    size: f32,
+) -> Option<Entity> {
+    if let Ok(children) = query.get(cover) {
+        for child in children.iter() {
+            commands.entity(*child).despawn_recursive();
         }
     }
+    spawn_mark(commands, cover, mark, board_assets, size)
+}
+
+/// Spawns the mark sprite of a tile cover, returning it if any
+pub(crate) fn spawn_mark(
+    commands: &mut Commands,
+    cover: Entity,
+    mark: Option<Mark>,
+    board_assets: &BoardAssets,
+    size: f32,
+) -> Option<Entity> {
+    let (material, name, kind) = match mark {
+        Some(Mark::Flag) => (&board_assets.flag_material, "Flag", BoardSprite::Flag),
+        Some(Mark::Question) => (
+            &board_assets.question_material,
+            "Question Mark",
+            BoardSprite::Question,
+        ),
+        None => return None,
+    };
+    let mut sprite = None;
+    commands.entity(cover).with_children(|parent| {
+        let entity = parent
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(size)),
+                    color: material.color,
+                    ..Default::default()
+                },
+                texture: material.texture.clone(),
+                transform: Transform::from_xyz(0., 0., 1.),
+                ..Default::default()
+            })
+            .insert(Name::new(name))
+            .insert(kind)
+            .id();
+        sprite = Some(entity);
+    });
+    sprite
 }
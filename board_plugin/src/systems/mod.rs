@@ -1,4 +1,21 @@
 pub mod input;
 pub mod uncover;
 pub mod exit_handler;
-pub mod mark;
\ No newline at end of file
+pub mod mark;
+pub mod history;
+pub mod animation;
+pub mod audio;
+pub mod theme;
+pub mod accessibility;
+pub mod settings;
+pub mod replay;
+pub mod bot;
+pub mod camera;
+pub mod chunks;
+pub mod endless;
+pub mod race;
+pub mod net;
+pub mod spectator;
+pub mod daily;
+pub mod puzzle;
+pub mod editor;
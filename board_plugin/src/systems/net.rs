@@ -0,0 +1,172 @@
+use crate::board::Mark;
+use crate::button_style::ExitWindowTitle;
+use crate::events::{
+    MovePlayedEvent, NewGameEvent, Sound, SoundEvent, TileMarkEvent, TileTriggerEvent,
+};
+use crate::net::{ClientMessage, NetAction, NetError, NetMode, ServerMessage};
+use crate::systems::race::spawn_hud;
+use crate::{
+    AppState, Board, BoardAssets, BoardOptions, BoardPlugin, ExitWindow, NetClient, RaceHud,
+    ReplayAction,
+};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Sends the moves of the player to the server instead of playing them
+pub fn forward_moves(
+    client: Res<NetClient>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+) {
+    let actions = tile_trigger_evr
+        .read()
+        .map(|event| NetAction::Reveal(event.0))
+        .chain(tile_mark_evr.read().map(|event| NetAction::Mark(event.0)));
+    for action in actions {
+        if let Err(e) = client.send(&ClientMessage::Action(action)) {
+            log::error!("Could not send {:?}: {}", action, e);
+        }
+    }
+}
+
+/// Asks the server for the next game, which starts for every player at once
+pub fn request_new_game(client: Res<NetClient>, mut new_game_evr: EventReader<NewGameEvent>) {
+    new_game_evr.clear();
+    if let Err(e) = client.send(&ClientMessage::NewGame) {
+        log::error!("Could not request a new game: {}", e);
+    }
+}
+
+/// Applies the moves and games sent by the server. Once the connection is lost, the game goes
+/// back to the local rules
+#[allow(clippy::too_many_arguments)]
+pub fn apply_server_messages(
+    mut commands: Commands,
+    mut client: ResMut<NetClient>,
+    mut board: Option<ResMut<Board>>,
+    board_options: Option<Res<BoardOptions>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    board_assets: Res<BoardAssets>,
+    exit_window: Query<Entity, With<ExitWindow>>,
+    state: Res<State<AppState>>,
+    mut exit_window_title: ResMut<ExitWindowTitle>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+    mut move_played_ewr: EventWriter<MovePlayedEvent>,
+) {
+    loop {
+        let message = match client.try_receive() {
+            Ok(Some(message)) => message,
+            Ok(None) => return,
+            Err(NetError::Closed) => {
+                log::warn!("Lost the connection to the server");
+                commands.remove_resource::<NetClient>();
+                exit_window_title.text = "CONNECTION LOST".into();
+                next_state.set(AppState::Out);
+                return;
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                continue;
+            }
+        };
+        let own_move = |player: usize| player == client.game.player;
+        match message {
+            ServerMessage::Start(game) => {
+                log::info!("Starting game as player {}", game.player + 1);
+                client.progress = vec![0; game.players];
+                client.game = game;
+                let options = board_options.as_deref().cloned().unwrap_or_default();
+                BoardPlugin::replace_board(
+                    &mut commands,
+                    board.as_deref(),
+                    client.board_options(options),
+                    windows.get_single().ok(),
+                    &board_assets,
+                    &exit_window,
+                    &mut next_state,
+                );
+                // The following moves are for the new board, applied once it is inserted
+                return;
+            }
+            ServerMessage::Revealed { player, coord } => {
+                if client.game.mode == NetMode::Versus && !own_move(player) {
+                    continue;
+                }
+                let Some(board) = board.as_mut() else {
+                    continue;
+                };
+                move_played_ewr.send(MovePlayedEvent(ReplayAction::Reveal(coord)));
+                if board.click(&coord) == Some(true) {
+                    sound_ewr.send(SoundEvent(Sound::Chord));
+                }
+            }
+            ServerMessage::Marked {
+                player,
+                coord,
+                mark,
+            } => {
+                if client.game.mode == NetMode::Versus && !own_move(player) {
+                    continue;
+                }
+                let Some(board) = board.as_mut() else {
+                    continue;
+                };
+                move_played_ewr.send(MovePlayedEvent(ReplayAction::Mark(coord)));
+                board.set_mark(&coord, mark);
+                sound_ewr.send(SoundEvent(if mark == Some(Mark::Flag) {
+                    Sound::Flag
+                } else {
+                    Sound::Unflag
+                }));
+            }
+            ServerMessage::Progress { player, progress } => {
+                if let Some(value) = client.progress.get_mut(player) {
+                    *value = progress;
+                }
+            }
+            ServerMessage::GameOver { winner } => {
+                // The moves of the player end their own game with the local rules
+                let pending = board.as_ref().is_some_and(|b| !b.uncovering.is_empty());
+                if *state.get() != AppState::InGame || pending {
+                    continue;
+                }
+                exit_window_title.text = match winner {
+                    Some(player) if client.game.mode == NetMode::Versus && !own_move(player) => {
+                        format!("PLAYER {} WINS!", player + 1)
+                    }
+                    Some(_) => "YOU WON!".into(),
+                    None => "GAME OVER!".into(),
+                };
+                next_state.set(AppState::Out);
+            }
+        }
+    }
+}
+
+/// Spawns the progress of the players of a versus game
+pub fn spawn_net_hud(
+    mut commands: Commands,
+    client: Res<NetClient>,
+    board_assets: Res<BoardAssets>,
+    huds: Query<(), With<RaceHud>>,
+) {
+    if client.game.mode == NetMode::Versus && huds.is_empty() {
+        spawn_hud(&mut commands, &board_assets, client.game.players);
+    }
+}
+
+pub fn update_net_hud(client: Res<NetClient>, mut huds: Query<(&mut Text, &RaceHud)>) {
+    for (mut text, hud) in &mut huds {
+        let Some(progress) = client.progress.get(hud.0) else {
+            continue;
+        };
+        let you = if hud.0 == client.game.player {
+            " (you)"
+        } else {
+            ""
+        };
+        text.sections[0].value = format!("PLAYER {}{}: {}%", hud.0 + 1, you, progress);
+    }
+}
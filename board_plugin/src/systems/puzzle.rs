@@ -0,0 +1,295 @@
+use crate::board::Mark;
+use crate::bot::probability::{mine_probabilities, EPSILON};
+use crate::button_style::{ButtonStyle, ExitWindowTitle};
+use crate::events::{
+    AnnounceEvent, GameOverEvent, Sound, SoundEvent, TileMarkEvent, TileTriggerEvent,
+};
+use crate::game::TileView;
+use crate::systems::exit_handler::{panel_bundle, spawn_button, spawn_title};
+use crate::{
+    AppState, Board, BoardAssets, BoardOptions, BoardPlugin, DailyGame, ExitWindow, PuzzleGame,
+    PuzzleGoal, PuzzleHud, PuzzlePacks, PuzzleWindow,
+};
+use bevy::input::ButtonInput;
+use bevy::log;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_round_ui::prelude::*;
+
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PuzzleAction {
+    Play { pack: usize, puzzle: usize },
+    Back,
+}
+
+/// Lists the puzzles, one column per pack
+pub fn setup_puzzle_window(
+    mut commands: Commands,
+    button_style: Res<ButtonStyle>,
+    packs: Res<PuzzlePacks>,
+    mut materials: ResMut<Assets<RoundUiMaterial>>,
+) {
+    let columns = packs.packs.len().max(1) as f32;
+    let rows = packs
+        .packs
+        .iter()
+        .map(|p| p.puzzles.len())
+        .max()
+        .unwrap_or(0) as f32;
+    let panel_width = (columns * (button_style.width + 20.) + 60.).max(300.);
+    let panel_height = rows * (button_style.height + 10.) + 260.;
+    let panel_material = materials.add(RoundUiMaterial {
+        background_color: button_style.panel_background,
+        border_color: button_style.panel_border,
+        border_radius: RoundUiBorder::all(20.0).into(),
+        size: Vec2::new(panel_width, panel_height),
+        offset: RoundUiOffset::all(6.0).into(),
+    });
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|p| {
+            p.spawn(panel_bundle(panel_material, panel_width, panel_height))
+                .with_children(|p| {
+                    spawn_title(p, "PUZZLES");
+                    p.spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|p| {
+                        for (pack_index, pack) in packs.packs.iter().enumerate() {
+                            p.spawn(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|p| {
+                                p.spawn(
+                                    TextBundle::from_section(
+                                        &pack.name,
+                                        TextStyle {
+                                            color: Color::WHITE,
+                                            font_size: 24.,
+                                            ..default()
+                                        },
+                                    )
+                                    .with_style(Style {
+                                        margin: UiRect::bottom(Val::Px(10.)),
+                                        ..default()
+                                    }),
+                                );
+                                for (index, puzzle) in pack.puzzles.iter().enumerate() {
+                                    let action = PuzzleAction::Play {
+                                        pack: pack_index,
+                                        puzzle: index,
+                                    };
+                                    spawn_button(p, &button_style, &puzzle.name, action);
+                                }
+                            });
+                        }
+                    });
+                    spawn_button(p, &button_style, "Back", PuzzleAction::Back);
+                });
+        })
+        .insert(PuzzleWindow);
+}
+
+/// Starts the picked puzzle, or goes back to the menu
+#[allow(clippy::too_many_arguments)]
+pub fn handle_puzzle_actions(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &PuzzleAction), Changed<Interaction>>,
+    window: Query<Entity, With<PuzzleWindow>>,
+    packs: Res<PuzzlePacks>,
+    board: Option<Res<Board>>,
+    board_options: Option<Res<BoardOptions>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    board_assets: Res<BoardAssets>,
+    exit_window: Query<Entity, With<ExitWindow>>,
+    huds: Query<Entity, With<PuzzleHud>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut mouse_input: ResMut<ButtonInput<MouseButton>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut announce_ewr: EventWriter<AnnounceEvent>,
+) {
+    let mut picked = None;
+    let mut back = keys.just_pressed(KeyCode::Escape);
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *action {
+            PuzzleAction::Play { pack, puzzle } => picked = Some((pack, puzzle)),
+            PuzzleAction::Back => back = true,
+        }
+    }
+    let puzzle = picked.and_then(|(pack, puzzle)| packs.packs.get(pack)?.puzzles.get(puzzle));
+    if puzzle.is_none() && !back {
+        return;
+    }
+    for entity in &window {
+        commands.entity(entity).despawn_recursive();
+    }
+    let Some(puzzle) = puzzle else {
+        next_state.set(AppState::Out);
+        return;
+    };
+    log::info!("Starting puzzle {}: {}", puzzle.name, puzzle.goal);
+    // Keep the click from also revealing a tile of the new board
+    mouse_input.clear();
+    commands.remove_resource::<DailyGame>();
+    BoardPlugin::replace_board(
+        &mut commands,
+        board.as_deref(),
+        puzzle.board_options(board_options.map(|o| o.clone()).unwrap_or_default()),
+        windows.get_single().ok(),
+        &board_assets,
+        &exit_window,
+        &mut next_state,
+    );
+    for entity in &huds {
+        commands.entity(entity).despawn_recursive();
+    }
+    let description = format!("{}: {}", puzzle.name, puzzle.goal);
+    commands
+        .spawn(hud_bundle(&board_assets, &description))
+        .insert(PuzzleHud);
+    announce_ewr.send(AnnounceEvent(description));
+    commands.insert_resource(PuzzleGame {
+        puzzle: puzzle.clone(),
+        guessed: false,
+    });
+}
+
+fn hud_bundle(board_assets: &BoardAssets, text: &str) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font: board_assets.bomb_counter_font.clone(),
+            font_size: 24.,
+            color: Color::WHITE,
+        },
+    )
+    .with_style(Style {
+        position_type: PositionType::Absolute,
+        top: Val::Px(10.),
+        left: Val::Px(10.),
+        ..default()
+    })
+}
+
+pub fn remove_puzzle_hud(mut commands: Commands, huds: Query<Entity, With<PuzzleHud>>) {
+    for entity in &huds {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Notes when a revealed tile could not be proven safe from the numbers
+pub fn check_guesses(
+    mut puzzle: ResMut<PuzzleGame>,
+    board: Res<Board>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+) {
+    if puzzle.guessed || puzzle.puzzle.goal != PuzzleGoal::NoGuess {
+        tile_trigger_evr.clear();
+        return;
+    }
+    // The probabilities are only worked out on the frames something is revealed
+    if tile_trigger_evr.is_empty() {
+        return;
+    }
+    // A chord reveals every unflagged neighbor of the number
+    let targets: Vec<_> = tile_trigger_evr
+        .read()
+        .flat_map(|event| board.click_targets(&event.0))
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    let mut view = board.view();
+    // The marks of the player may be wrong, only the flags of the puzzle are known to be right
+    for (index, tile) in view.tiles.iter_mut().enumerate() {
+        let coord = board.states.coordinate(index);
+        if matches!(tile, TileView::Covered(Some(_)))
+            && !puzzle.puzzle.start.flagged.contains(&coord)
+        {
+            *tile = TileView::Covered(None);
+        }
+    }
+    // A reveal that cannot be verified is not counted as proven safe
+    let Some(probabilities) = mine_probabilities(&view) else {
+        log::info!("The reveal could not be verified, it counts as a guess");
+        puzzle.guessed = true;
+        return;
+    };
+    for target in targets {
+        let safe = probabilities
+            .iter()
+            .any(|(coord, p)| *coord == target && *p < EPSILON);
+        if !safe {
+            log::info!("{} could not be proven safe", target);
+            puzzle.guessed = true;
+        }
+    }
+}
+
+/// Wins a puzzle to flag the mines once every mine and nothing else is flagged
+pub fn solve_flag_puzzle(
+    puzzle: Res<PuzzleGame>,
+    board: Res<Board>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+    mut game_over_ewr: EventWriter<GameOverEvent>,
+) {
+    if tile_mark_evr.read().count() == 0 || puzzle.puzzle.goal != PuzzleGoal::FlagMines {
+        return;
+    }
+    // A loss or a clear in the same frame already ended the game
+    if board.exploded.is_some() || board.is_completed() {
+        return;
+    }
+    let mines = board.tile_map.bomb_coordinates();
+    let flagged = board.states.flag_count() as usize == mines.len()
+        && mines
+            .iter()
+            .all(|coord| board.mark_at(coord) == Some(Mark::Flag));
+    if flagged {
+        log::info!("Every mine is flagged");
+        sound_ewr.send(SoundEvent(Sound::Win));
+        game_over_ewr.send(GameOverEvent::Won);
+        next_state.set(AppState::Out);
+    }
+}
+
+/// Titles the menu with the result of the puzzle
+pub fn show_puzzle_result(
+    puzzle: Res<PuzzleGame>,
+    mut exit_window_title: ResMut<ExitWindowTitle>,
+    mut game_over_evr: EventReader<GameOverEvent>,
+) {
+    for event in game_over_evr.read() {
+        if *event == GameOverEvent::Won {
+            exit_window_title.text = if puzzle.guessed {
+                "SOLVED WITH A GUESS".into()
+            } else {
+                "PUZZLE SOLVED!".into()
+            };
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use crate::board::Mark;
+use crate::button_style::ExitWindowTitle;
+use crate::events::{NewGameEvent, RaceMarkEvent, RaceTriggerEvent, Sound, SoundEvent};
+use crate::systems::uncover::{spawn_board_explosion, spawn_cover_tweens};
+use crate::{
+    AnimationOptions, AppState, Board, BoardAssets, BoardOptions, BoardPlugin, BoardPosition,
+    Coordinate, ExitWindow, KeyBindings, Race, RaceHud, RacePlayer, TileCursor, TileSize,
+};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Space between the boards and around them
+const GAP: f32 = 20.;
+/// Space kept at the top of the window for the progress of the players
+const HUD_HEIGHT: f32 = 50.;
+
+/// Starts a race unless one was left for the menu
+#[allow(clippy::too_many_arguments)]
+pub fn create_race(
+    mut commands: Commands,
+    race: Option<Res<Race>>,
+    board_options: Option<Res<BoardOptions>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    board_assets: Res<BoardAssets>,
+    time: Res<Time>,
+    huds: Query<(), With<RaceHud>>,
+) {
+    if huds.is_empty() {
+        spawn_hud(&mut commands, &board_assets, 2);
+    }
+    if race.is_some() {
+        return;
+    }
+    let race = spawn_race(
+        &mut commands,
+        board_options.map(|o| o.clone()).unwrap_or_default(),
+        windows.get_single().ok(),
+        &board_assets,
+    );
+    commands.insert_resource(Race {
+        started: time.elapsed(),
+        ..race
+    });
+}
+
+/// Replaces the boards with new identical ones and starts racing, whatever the state
+#[allow(clippy::too_many_arguments)]
+pub fn new_race(
+    mut commands: Commands,
+    mut new_game_evr: EventReader<NewGameEvent>,
+    boards: Query<Entity, With<RacePlayer>>,
+    board_options: Option<Res<BoardOptions>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    board_assets: Res<BoardAssets>,
+    time: Res<Time>,
+    exit_window: Query<Entity, With<ExitWindow>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    new_game_evr.clear();
+    for entity in boards.iter().chain(exit_window.iter()) {
+        commands.entity(entity).despawn_recursive();
+    }
+    // Removed first so the new race counts as added
+    commands.remove_resource::<Race>();
+    let race = spawn_race(
+        &mut commands,
+        board_options.map(|o| o.clone()).unwrap_or_default(),
+        windows.get_single().ok(),
+        &board_assets,
+    );
+    commands.insert_resource(Race {
+        started: time.elapsed(),
+        ..race
+    });
+    next_state.set(AppState::InGame);
+}
+
+/// Spawns the boards of both players side by side, from the same seed. Adaptive tiles fit both
+/// boards in the window
+fn spawn_race(
+    commands: &mut Commands,
+    options: BoardOptions,
+    window: Option<&Window>,
+    board_assets: &BoardAssets,
+) -> Race {
+    let seed = options.seed.unwrap_or_else(rand::random);
+    log::info!("Race seed: {}", seed);
+    let (width, height) = match &options.layout {
+        Some(layout) => (layout.width, layout.height),
+        None => options.map_size,
+    };
+    let tile_size = match options.tile_size {
+        TileSize::Fixed(v) => v,
+        TileSize::Adaptive { min, max } => match window {
+            Some(window) => {
+                let max_width = (window.width() - GAP * 3.) / 2. / width as f32;
+                let max_height = (window.height() - HUD_HEIGHT - GAP) / height as f32;
+                max_width.min(max_height).clamp(min, max)
+            }
+            None => max,
+        },
+    };
+    let board_size = Vec2::new(width as f32, height as f32) * tile_size;
+    let y = -board_size.y / 2. - HUD_HEIGHT / 2.;
+    for (player, x) in [(0, -GAP / 2. - board_size.x), (1, GAP / 2.)] {
+        let options = BoardOptions {
+            seed: Some(seed),
+            tile_size: TileSize::Fixed(tile_size),
+            position: BoardPosition::Custom(Vec3::new(x, y, 0.)),
+            // Moving a mine would make the boards differ
+            safe_start: false,
+            ..options.clone()
+        };
+        let mut board = BoardPlugin::spawn_board(commands, options, None, board_assets);
+        board.cursor = Coordinate {
+            x: width / 2,
+            y: height / 2,
+        };
+        commands
+            .entity(board.entity)
+            .insert(Name::new(format!("Board {}", player + 1)))
+            .insert(RacePlayer(player));
+        let entity = board.entity;
+        commands.entity(entity).insert(board);
+    }
+    Race {
+        seed,
+        started: Default::default(),
+        times: [None; 2],
+        winner: None,
+    }
+}
+
+/// Spawns a row of texts showing the progress of each player
+pub(crate) fn spawn_hud(commands: &mut Commands, board_assets: &BoardAssets, players: usize) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.),
+                width: Val::Percent(100.),
+                justify_content: JustifyContent::SpaceAround,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Name::new("Progress HUD"))
+        .with_children(|parent| {
+            for player in 0..players {
+                parent
+                    .spawn(TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: board_assets.bomb_counter_font.clone(),
+                            font_size: 24.,
+                            color: Color::WHITE,
+                        },
+                    ))
+                    .insert(RaceHud(player));
+            }
+        });
+}
+
+/// The first player reveals and flags with the mouse on the left board
+pub fn race_mouse_input(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut boards: Query<(&mut Board, &RacePlayer)>,
+    input: Res<ButtonInput<MouseButton>>,
+    mut trigger_ewr: EventWriter<RaceTriggerEvent>,
+    mut mark_ewr: EventWriter<RaceMarkEvent>,
+) {
+    let (Ok(window), Ok((camera, transform))) = (windows.get_single(), cameras.get_single()) else {
+        return;
+    };
+    let Some(position) = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world_2d(transform, position))
+    else {
+        return;
+    };
+    for (mut board, player) in &mut boards {
+        if player.0 != 0 {
+            continue;
+        }
+        let Some(coord) = board.mouse_position(position) else {
+            continue;
+        };
+        if board.cursor != coord {
+            board.cursor = coord;
+        }
+        if input.just_pressed(MouseButton::Left) {
+            trigger_ewr.send(RaceTriggerEvent { player: 0, coord });
+        } else if input.just_pressed(MouseButton::Right) {
+            mark_ewr.send(RaceMarkEvent { player: 0, coord });
+        }
+    }
+}
+
+/// The second player moves a cursor on the right board with the keyboard
+pub fn race_keyboard_input(
+    mut boards: Query<(&mut Board, &RacePlayer)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut trigger_ewr: EventWriter<RaceTriggerEvent>,
+    mut mark_ewr: EventWriter<RaceMarkEvent>,
+) {
+    let moves = [
+        (bindings.up, (0, 1)),
+        (bindings.down, (0, -1)),
+        (bindings.left, (-1, 0)),
+        (bindings.right, (1, 0)),
+    ];
+    for (mut board, player) in &mut boards {
+        if player.0 != 1 {
+            continue;
+        }
+        for (key, (dx, dy)) in moves {
+            if keys.just_pressed(key) {
+                board.move_cursor(dx, dy);
+            }
+        }
+        let coord = board.cursor;
+        if keys.just_pressed(bindings.reveal) {
+            trigger_ewr.send(RaceTriggerEvent { player: 1, coord });
+        } else if keys.just_pressed(bindings.flag) {
+            mark_ewr.send(RaceMarkEvent { player: 1, coord });
+        }
+    }
+}
+
+/// Reveals the tiles of each player. The race is won by clearing a board first, or by the other
+/// player hitting a mine
+#[allow(clippy::too_many_arguments)]
+pub fn reveal_race_tiles(
+    mut commands: Commands,
+    mut boards: Query<(&mut Board, &RacePlayer)>,
+    mut race: ResMut<Race>,
+    board_assets: Res<BoardAssets>,
+    animations: Option<Res<AnimationOptions>>,
+    time: Res<Time>,
+    mut trigger_evr: EventReader<RaceTriggerEvent>,
+    mut exit_window_title: ResMut<ExitWindowTitle>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+) {
+    let animations = animations.filter(|a| a.enabled);
+    for event in trigger_evr.read() {
+        let Some((mut board, _)) = boards.iter_mut().find(|(_, p)| p.0 == event.player) else {
+            continue;
+        };
+        // A click on a satisfied number chords it, like on the single board
+        let targets = board.click_targets(&event.coord);
+        if targets.is_empty() {
+            continue;
+        }
+        let chord = !board.states.is_covered(event.coord);
+        board.begin_reveal(event.coord);
+        let revealed: Vec<Coordinate> = targets
+            .iter()
+            .flat_map(|target| board.reveal(target))
+            .collect();
+        if let Some(animations) = &animations {
+            spawn_cover_tweens(
+                &mut commands,
+                &board,
+                &board_assets,
+                animations,
+                event.coord,
+                &revealed,
+            );
+        }
+        let mine = targets
+            .iter()
+            .copied()
+            .find(|target| board.tile_map.is_bomb_at(*target));
+        if let Some(mine) = mine {
+            log::info!("Player {} hit a mine", event.player + 1);
+            sound_ewr.send(SoundEvent(Sound::Explosion));
+            board.exploded = Some(mine);
+            board.tile_changed(mine);
+            if animations.as_ref().is_some_and(|a| a.explosion) {
+                spawn_board_explosion(&mut commands, &board, &board_assets, mine);
+            }
+            race.winner = Some(1 - event.player);
+        } else if board.is_completed() {
+            log::info!("Player {} cleared the board", event.player + 1);
+            race.times[event.player] = Some(time.elapsed().saturating_sub(race.started));
+            race.winner = Some(event.player);
+        } else if chord {
+            sound_ewr.send(SoundEvent(Sound::Chord));
+        } else {
+            sound_ewr.send(SoundEvent(
+                if board.tile_map.bomb_count_at(event.coord) == 0 {
+                    Sound::Opening
+                } else {
+                    Sound::Reveal
+                },
+            ));
+        }
+        if let Some(winner) = race.winner {
+            // Winning because the other player hit a mine only plays the explosion
+            if race.times[winner].is_some() {
+                sound_ewr.send(SoundEvent(Sound::Win));
+            }
+            exit_window_title.text = format!("PLAYER {} WINS!", winner + 1);
+            // Both boards show where the mines were
+            for (mut board, _) in &mut boards {
+                board.covers_hidden = true;
+                board.all_tiles_changed();
+            }
+            next_state.set(AppState::Out);
+            break;
+        }
+    }
+}
+
+/// Cycles the marks of the tiles of each player
+pub fn mark_race_tiles(
+    mut boards: Query<(&mut Board, &RacePlayer)>,
+    mut mark_evr: EventReader<RaceMarkEvent>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+) {
+    for event in mark_evr.read() {
+        let Some((mut board, _)) = boards.iter_mut().find(|(_, p)| p.0 == event.player) else {
+            continue;
+        };
+        let Some(mark) = board.try_toggle_mark(&event.coord) else {
+            continue;
+        };
+        log::debug!(
+            "Player {} marked tile {} as {:?}",
+            event.player + 1,
+            event.coord,
+            mark
+        );
+        sound_ewr.send(SoundEvent(if mark == Some(Mark::Flag) {
+            Sound::Flag
+        } else {
+            Sound::Unflag
+        }));
+    }
+}
+
+/// Keeps the cursor highlight of each board on its cursor tile
+pub fn update_race_cursors(
+    boards: Query<&Board, With<RacePlayer>>,
+    mut cursors: Query<(&Parent, &mut Transform), With<TileCursor>>,
+) {
+    for (parent, mut transform) in &mut cursors {
+        let Ok(board) = boards.get(parent.get()) else {
+            continue;
+        };
+        transform.translation.x = (board.cursor.x as f32 + 0.5) * board.tile_size;
+        transform.translation.y = (board.cursor.y as f32 + 0.5) * board.tile_size;
+    }
+}
+
+/// Shows how much of their board each player cleared, and for how long they played
+pub fn update_race_hud(
+    race: Res<Race>,
+    time: Res<Time>,
+    boards: Query<(&Board, &RacePlayer)>,
+    mut huds: Query<(&mut Text, &RaceHud)>,
+) {
+    for (board, player) in &boards {
+        let tiles = board.tile_map.width() as u32 * board.tile_map.height() as u32;
+        let safe_tiles = tiles - board.tile_map.bomb_count();
+        let cleared = tiles - board.states.covered_count();
+        let progress = cleared as f32 * 100. / safe_tiles.max(1) as f32;
+        let played = race.time(player.0, time.elapsed());
+        for (mut text, hud) in &mut huds {
+            if hud.0 == player.0 {
+                text.sections[0].value = format!(
+                    "{}: {:.0}% {:.1}s",
+                    Race::PLAYERS[player.0],
+                    progress,
+                    played.as_secs_f32()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::events::{HistoryEvent, MovePlayedEvent, TileMarkEvent, TileTriggerEvent};
+use crate::{Board, Layout, Replay, ReplayAction, ReplayMove, ReplayPlayback, ReplayRecorder};
+use bevy::log;
+use bevy::prelude::*;
+
+/// Starts recording a new board, a game resumed with undo keeps its recording
+pub fn start_recording(board: Res<Board>, mut recorder: ResMut<ReplayRecorder>) {
+    recorder.replay = Some(Replay {
+        layout: Layout::from_tile_map(&board.tile_map),
+        moves: Vec::new(),
+    });
+    recorder.elapsed = 0.;
+}
+
+pub fn record_moves(
+    time: Res<Time>,
+    board: Res<Board>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut move_played_evr: EventReader<MovePlayedEvent>,
+) {
+    recorder.elapsed += time.delta_seconds();
+    let elapsed = recorder.elapsed;
+    let Some(replay) = recorder.replay.as_mut() else {
+        move_played_evr.clear();
+        return;
+    };
+    // The replay starts from the board as it was revealed, its layout is played without safe
+    // start
+    if let Some((from, to)) = board.moved_mine {
+        replay.layout.move_mine(from, to);
+    }
+    for event in move_played_evr.read() {
+        replay.moves.push(ReplayMove {
+            time: elapsed,
+            action: event.0,
+        });
+    }
+}
+
+/// The board applies the reveals of a frame first, then the marks, then undo and redo
+pub(crate) fn applied_order(action: ReplayAction) -> u8 {
+    match action {
+        ReplayAction::Reveal(_) => 0,
+        ReplayAction::Mark(_) => 1,
+        ReplayAction::Undo | ReplayAction::Redo => 2,
+    }
+}
+
+pub fn save_recording(recorder: Res<ReplayRecorder>) {
+    let Some(replay) = &recorder.replay else {
+        return;
+    };
+    match replay.write(&recorder.path) {
+        Ok(()) => log::info!("Saved replay to {}", recorder.path.display()),
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// Sends the moves of the replay once their time has come
+pub fn play_replay(
+    time: Res<Time>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut history_ewr: EventWriter<HistoryEvent>,
+) {
+    playback.elapsed += time.delta_seconds();
+    let mut previous = None;
+    while let Some(next) = playback.replay.moves.get(playback.next).copied() {
+        if next.time > playback.elapsed {
+            break;
+        }
+        // A move the board would apply before the previous one waits for the next frame
+        let order = applied_order(next.action);
+        if previous.is_some_and(|previous| order < previous) {
+            break;
+        }
+        previous = Some(order);
+        match next.action {
+            ReplayAction::Reveal(coord) => {
+                tile_trigger_ewr.send(TileTriggerEvent(coord));
+            }
+            ReplayAction::Mark(coord) => {
+                tile_mark_ewr.send(TileMarkEvent(coord));
+            }
+            ReplayAction::Undo => {
+                history_ewr.send(HistoryEvent::Undo);
+            }
+            ReplayAction::Redo => {
+                history_ewr.send(HistoryEvent::Redo);
+            }
+        }
+        playback.next += 1;
+    }
+}
+
+/// The following games are played normally
+pub fn stop_playback(mut commands: Commands) {
+    commands.remove_resource::<ReplayPlayback>();
+}
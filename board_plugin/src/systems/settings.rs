@@ -0,0 +1,372 @@
+use crate::button_style::ButtonStyle;
+use crate::systems::exit_handler::{panel_bundle, spawn_button, spawn_title};
+use crate::theme::{Theme, Themes};
+use crate::{
+    AnimationOptions, AppState, AudioSettings, BoardOptions, KeyBindings, NumberPalette,
+    RoundButton, Settings, SettingsWindow, TileSize,
+};
+use bevy::a11y::AccessibilityNode;
+use bevy::input::ButtonInput;
+use bevy::log;
+use bevy::prelude::*;
+use bevy_round_ui::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Reveal,
+    Flag,
+    Undo,
+    Redo,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 8] = [
+        KeyAction::Up,
+        KeyAction::Down,
+        KeyAction::Left,
+        KeyAction::Right,
+        KeyAction::Reveal,
+        KeyAction::Flag,
+        KeyAction::Undo,
+        KeyAction::Redo,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            KeyAction::Up => "Up",
+            KeyAction::Down => "Down",
+            KeyAction::Left => "Left",
+            KeyAction::Right => "Right",
+            KeyAction::Reveal => "Reveal",
+            KeyAction::Flag => "Flag",
+            KeyAction::Undo => "Undo",
+            KeyAction::Redo => "Redo",
+        }
+    }
+
+    fn key(self, bindings: &KeyBindings) -> KeyCode {
+        match self {
+            KeyAction::Up => bindings.up,
+            KeyAction::Down => bindings.down,
+            KeyAction::Left => bindings.left,
+            KeyAction::Right => bindings.right,
+            KeyAction::Reveal => bindings.reveal,
+            KeyAction::Flag => bindings.flag,
+            KeyAction::Undo => bindings.undo,
+            KeyAction::Redo => bindings.redo,
+        }
+    }
+
+    fn key_mut(self, bindings: &mut KeyBindings) -> &mut KeyCode {
+        match self {
+            KeyAction::Up => &mut bindings.up,
+            KeyAction::Down => &mut bindings.down,
+            KeyAction::Left => &mut bindings.left,
+            KeyAction::Right => &mut bindings.right,
+            KeyAction::Reveal => &mut bindings.reveal,
+            KeyAction::Flag => &mut bindings.flag,
+            KeyAction::Undo => &mut bindings.undo,
+            KeyAction::Redo => &mut bindings.redo,
+        }
+    }
+}
+
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub enum SettingsAction {
+    TilePadding,
+    TileSize,
+    SafeStart,
+    QuestionMarks,
+    Animations,
+    Theme,
+    Palette,
+    Glyphs,
+    Volume,
+    SfxVolume,
+    Sound,
+    Key(KeyAction),
+    Back,
+}
+
+const GENERAL: [SettingsAction; 11] = [
+    SettingsAction::TilePadding,
+    SettingsAction::TileSize,
+    SettingsAction::SafeStart,
+    SettingsAction::QuestionMarks,
+    SettingsAction::Animations,
+    SettingsAction::Theme,
+    SettingsAction::Palette,
+    SettingsAction::Glyphs,
+    SettingsAction::Volume,
+    SettingsAction::SfxVolume,
+    SettingsAction::Sound,
+];
+
+const TILE_SIZES: [Option<f32>; 4] = [None, Some(20.), Some(30.), Some(40.)];
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "On"
+    } else {
+        "Off"
+    }
+}
+
+/// Next volume in quarters, back to silent after the full volume
+fn next_volume(volume: f32) -> f32 {
+    let volume = (volume * 4.).round() + 1.;
+    if volume > 4. {
+        0.
+    } else {
+        volume / 4.
+    }
+}
+
+fn label(action: SettingsAction, settings: &Settings, theme_name: &str) -> String {
+    match action {
+        SettingsAction::TilePadding => format!("Padding: {}", settings.tile_padding),
+        SettingsAction::TileSize => match settings.tile_size {
+            TileSize::Fixed(size) => format!("Tiles: {size}px"),
+            TileSize::Adaptive { .. } => "Tiles: Adaptive".to_string(),
+        },
+        SettingsAction::SafeStart => format!("Safe start: {}", on_off(settings.safe_start)),
+        SettingsAction::QuestionMarks => format!("Marks ?: {}", on_off(settings.question_marks)),
+        SettingsAction::Animations => format!("Animations: {}", on_off(settings.animations)),
+        SettingsAction::Theme => format!("Theme: {theme_name}"),
+        SettingsAction::Palette => format!("Palette: {:?}", settings.palette.kind),
+        SettingsAction::Glyphs => format!("Glyphs: {}", on_off(settings.palette.glyphs)),
+        SettingsAction::Volume => {
+            format!("Volume: {}%", (settings.audio.master_volume * 100.).round())
+        }
+        SettingsAction::SfxVolume => {
+            format!("Effects: {}%", (settings.audio.sfx_volume * 100.).round())
+        }
+        SettingsAction::Sound => format!("Sound: {}", on_off(!settings.audio.muted)),
+        SettingsAction::Key(key) => {
+            format!("{}: {:?}", key.name(), key.key(&settings.key_bindings))
+        }
+        SettingsAction::Back => "Back".to_string(),
+    }
+}
+
+fn theme_name(
+    settings: &Settings,
+    themes: Option<&Themes>,
+    theme_assets: &Assets<Theme>,
+) -> String {
+    themes
+        .and_then(|themes| themes.list.get(settings.theme))
+        .and_then(|handle| theme_assets.get(handle))
+        .map(|theme| theme.board_assets.label.clone())
+        .unwrap_or_else(|| settings.theme.to_string())
+}
+
+pub fn setup_settings_window(
+    mut commands: Commands,
+    button_style: Res<ButtonStyle>,
+    settings: Res<Settings>,
+    themes: Option<Res<Themes>>,
+    theme_assets: Res<Assets<Theme>>,
+    mut materials: ResMut<Assets<RoundUiMaterial>>,
+) {
+    let panel_width = 500.0;
+    let panel_height = 680.0;
+    let panel_material = materials.add(RoundUiMaterial {
+        background_color: button_style.panel_background,
+        border_color: button_style.panel_border,
+        border_radius: RoundUiBorder::all(20.0).into(),
+        size: Vec2::new(panel_width, panel_height),
+        offset: RoundUiOffset::all(6.0).into(),
+    });
+    let theme_name = theme_name(&settings, themes.as_deref(), &theme_assets);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|p| {
+            p.spawn(panel_bundle(panel_material, panel_width, panel_height))
+                .with_children(|p| {
+                    spawn_title(p, "SETTINGS");
+                    p.spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|p| {
+                        let general = GENERAL.to_vec();
+                        let keys = KeyAction::ALL.map(SettingsAction::Key).to_vec();
+                        for column in [general, keys] {
+                            p.spawn(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|p| {
+                                for action in column {
+                                    let text = label(action, &settings, &theme_name);
+                                    spawn_button(p, &button_style, text, action);
+                                }
+                            });
+                        }
+                    });
+                    spawn_button(p, &button_style, "Back", SettingsAction::Back);
+                });
+        })
+        .insert(SettingsWindow);
+}
+
+/// Edits the settings, a key binding button waits for the next key press
+#[allow(clippy::too_many_arguments)]
+pub fn handle_settings_actions(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &SettingsAction, &Children), Changed<Interaction>>,
+    mut texts: Query<&mut Text>,
+    window: Query<Entity, With<SettingsWindow>>,
+    mut settings: ResMut<Settings>,
+    themes: Option<Res<Themes>>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut pending: Local<Option<KeyAction>>,
+) {
+    if let Some(action) = *pending {
+        let key = keys.get_just_pressed().next().copied();
+        if let Some(key) = key {
+            if key != KeyCode::Escape {
+                *action.key_mut(&mut settings.key_bindings) = key;
+            }
+            // Refresh the label even if the binding was cancelled
+            settings.set_changed();
+            // Keep the key from also pressing a menu button
+            keys.clear_just_pressed(key);
+            *pending = None;
+        }
+        return;
+    }
+    let mut back = keys.just_pressed(KeyCode::Escape);
+    for (interaction, action, children) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *action {
+            SettingsAction::TilePadding => {
+                settings.tile_padding = (settings.tile_padding + 1.) % 4.;
+            }
+            SettingsAction::TileSize => {
+                let current = match settings.tile_size {
+                    TileSize::Fixed(size) => Some(size),
+                    TileSize::Adaptive { .. } => None,
+                };
+                let index = TILE_SIZES.iter().position(|s| *s == current).unwrap_or(0);
+                settings.tile_size = match TILE_SIZES[(index + 1) % TILE_SIZES.len()] {
+                    Some(size) => TileSize::Fixed(size),
+                    None => TileSize::default(),
+                };
+            }
+            SettingsAction::SafeStart => settings.safe_start = !settings.safe_start,
+            SettingsAction::QuestionMarks => settings.question_marks = !settings.question_marks,
+            SettingsAction::Animations => settings.animations = !settings.animations,
+            SettingsAction::Theme => {
+                let count = themes.as_ref().map_or(1, |t| t.list.len().max(1));
+                settings.theme = (settings.theme + 1) % count;
+            }
+            SettingsAction::Palette => settings.palette.kind = settings.palette.kind.next(),
+            SettingsAction::Glyphs => settings.palette.glyphs = !settings.palette.glyphs,
+            SettingsAction::Volume => {
+                settings.audio.master_volume = next_volume(settings.audio.master_volume);
+            }
+            SettingsAction::SfxVolume => {
+                settings.audio.sfx_volume = next_volume(settings.audio.sfx_volume);
+            }
+            SettingsAction::Sound => settings.audio.muted = !settings.audio.muted,
+            SettingsAction::Key(key) => {
+                *pending = Some(key);
+                for child in children.iter() {
+                    if let Ok(mut text) = texts.get_mut(*child) {
+                        text.sections[0].value = format!("{}: press a key", key.name());
+                    }
+                }
+            }
+            SettingsAction::Back => back = true,
+        }
+    }
+    if back {
+        if let Err(e) = settings.save() {
+            log::error!("{}", e);
+        }
+        for entity in &window {
+            commands.entity(entity).despawn_recursive();
+        }
+        next_state.set(AppState::Out);
+    }
+}
+
+/// Updates the button labels and their accessible names to the current settings
+#[allow(clippy::type_complexity)]
+pub fn refresh_settings_labels(
+    settings: Res<Settings>,
+    themes: Option<Res<Themes>>,
+    theme_assets: Res<Assets<Theme>>,
+    mut buttons: Query<(&SettingsAction, &Children, &mut AccessibilityNode), With<RoundButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    let theme_name = theme_name(&settings, themes.as_deref(), &theme_assets);
+    for (action, children, mut node) in &mut buttons {
+        let text = label(*action, &settings, &theme_name);
+        for child in children.iter() {
+            if let Ok(mut button_text) = texts.get_mut(*child) {
+                button_text.sections[0].value.clone_from(&text);
+            }
+        }
+        node.set_name(text);
+    }
+}
+
+/// Copies the settings to the resources they control. Board options are used for the next
+/// game
+#[allow(clippy::too_many_arguments)]
+pub fn apply_settings(
+    settings: Res<Settings>,
+    board_options: Option<ResMut<BoardOptions>>,
+    animations: Option<ResMut<AnimationOptions>>,
+    themes: Option<ResMut<Themes>>,
+    mut palette: ResMut<NumberPalette>,
+    mut audio: ResMut<AudioSettings>,
+    mut key_bindings: ResMut<KeyBindings>,
+) {
+    if let Some(mut options) = board_options {
+        options.tile_padding = settings.tile_padding;
+        options.tile_size = settings.tile_size.clone();
+        options.safe_start = settings.safe_start;
+        options.question_marks = settings.question_marks;
+    }
+    if let Some(mut animations) = animations {
+        animations.enabled = settings.animations;
+    }
+    // Only flag the theme and palette as changed when they are, as it restyles the board
+    if let Some(mut themes) = themes {
+        if settings.theme < themes.list.len() && themes.current != settings.theme {
+            themes.current = settings.theme;
+        }
+    }
+    if *palette != settings.palette {
+        *palette = settings.palette.clone();
+    }
+    *audio = settings.audio.clone();
+    *key_bindings = settings.key_bindings.clone();
+}
@@ -0,0 +1,118 @@
+use crate::button_style::ExitWindowTitle;
+use crate::events::{HistoryEvent, MovePlayedEvent, TileMarkEvent, TileTriggerEvent};
+use crate::net::{NetError, SpectatorMessage};
+use crate::systems::replay::applied_order;
+use crate::{
+    AppState, Board, BoardAssets, BoardOptions, BoardPlugin, ExitWindow, Layout, ReplayAction,
+    SpectatorClient, SpectatorHost,
+};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+pub fn accept_spectators(mut host: ResMut<SpectatorHost>) {
+    host.accept();
+}
+
+/// Sends each new board to the spectators
+pub fn start_broadcast(board: Res<Board>, mut host: ResMut<SpectatorHost>) {
+    host.start(Layout::from_tile_map(&board.tile_map), board.question_marks);
+}
+
+/// Sends the moves of the player as they are made
+pub fn broadcast_moves(
+    time: Res<Time>,
+    board: Option<Res<Board>>,
+    mut host: ResMut<SpectatorHost>,
+    mut move_played_evr: EventReader<MovePlayedEvent>,
+) {
+    host.elapsed += time.delta_seconds();
+    if let Some((from, to)) = board.and_then(|board| board.moved_mine) {
+        host.move_mine(from, to);
+    }
+    for event in move_played_evr.read() {
+        host.push(event.0);
+    }
+}
+
+/// Plays the boards and moves of the watched game with the rules, like a replay. Once the
+/// connection is lost, the game can be played locally
+#[allow(clippy::too_many_arguments)]
+pub fn watch_game(
+    mut commands: Commands,
+    mut spectator: ResMut<SpectatorClient>,
+    board: Option<Res<Board>>,
+    board_options: Option<Res<BoardOptions>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    board_assets: Res<BoardAssets>,
+    exit_window: Query<Entity, With<ExitWindow>>,
+    mut exit_window_title: ResMut<ExitWindowTitle>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut history_ewr: EventWriter<HistoryEvent>,
+) {
+    let mut previous = None;
+    loop {
+        let message = match spectator.try_receive() {
+            Ok(Some(message)) => message,
+            Ok(None) => return,
+            Err(NetError::Closed) => {
+                log::warn!("Lost the connection to the watched game");
+                commands.remove_resource::<SpectatorClient>();
+                exit_window_title.text = "CONNECTION LOST".into();
+                next_state.set(AppState::Out);
+                return;
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                continue;
+            }
+        };
+        if let SpectatorMessage::Move(action) = message {
+            // A move the board would apply before the previous one waits for the next frame
+            let order = applied_order(action);
+            if previous.is_some_and(|previous| order < previous) {
+                spectator.put_back(message);
+                return;
+            }
+            previous = Some(order);
+        }
+        match message {
+            SpectatorMessage::Start {
+                layout,
+                question_marks,
+            } => {
+                let options = board_options.as_deref().cloned().unwrap_or_default();
+                BoardPlugin::replace_board(
+                    &mut commands,
+                    board.as_deref(),
+                    BoardOptions {
+                        layout: Some(layout),
+                        question_marks,
+                        practice_mode: false,
+                        ..options
+                    },
+                    windows.get_single().ok(),
+                    &board_assets,
+                    &exit_window,
+                    &mut next_state,
+                );
+                // The following moves are for the new board, sent once it is inserted
+                return;
+            }
+            SpectatorMessage::Move(ReplayAction::Reveal(coord)) => {
+                tile_trigger_ewr.send(TileTriggerEvent(coord));
+            }
+            SpectatorMessage::Move(ReplayAction::Mark(coord)) => {
+                tile_mark_ewr.send(TileMarkEvent(coord));
+            }
+            SpectatorMessage::Move(ReplayAction::Undo) => {
+                history_ewr.send(HistoryEvent::Undo);
+            }
+            SpectatorMessage::Move(ReplayAction::Redo) => {
+                history_ewr.send(HistoryEvent::Redo);
+            }
+        }
+    }
+}
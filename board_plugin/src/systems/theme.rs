@@ -0,0 +1,119 @@
+use crate::theme::{Theme, Themes};
+use crate::{
+    AppState, BoardAssets, BoardSprite, BombNeighbor, ButtonStyle, NumberGlyph, NumberPalette,
+    Tween,
+};
+use bevy::asset::LoadState;
+use bevy::log;
+use bevy::prelude::*;
+use bevy_round_ui::prelude::RoundUiMaterial;
+
+/// Replaces `BoardAssets` and the menu colors when the current theme is picked, loaded or
+/// modified on disk
+pub fn apply_theme(
+    mut commands: Commands,
+    themes: Res<Themes>,
+    theme_assets: Res<Assets<Theme>>,
+    mut theme_evr: EventReader<AssetEvent<Theme>>,
+    mut button_style: ResMut<ButtonStyle>,
+    mut materials: ResMut<Assets<RoundUiMaterial>>,
+) {
+    let current = themes.current().id();
+    let reloaded = theme_evr
+        .read()
+        .any(|event| event.is_added(current) || event.is_modified(current));
+    if !reloaded && !themes.is_changed() {
+        return;
+    }
+    let Some(theme) = theme_assets.get(current) else {
+        // Applied once loaded
+        return;
+    };
+    log::info!("Applying theme {}", theme.board_assets.label);
+    commands.insert_resource(theme.board_assets.clone());
+
+    let buttons = [
+        (button_style.default.clone(), theme.buttons.default),
+        (button_style.hover.clone(), theme.buttons.hover),
+        (button_style.press.clone(), theme.buttons.press),
+    ];
+    for (handle, colors) in buttons {
+        if let Some(material) = materials.get_mut(handle) {
+            material.background_color = colors.background;
+            material.border_color = colors.border;
+        }
+    }
+    button_style.panel_background = theme.panel.background;
+    button_style.panel_border = theme.panel.border;
+}
+
+/// Updates the sprites and numbers of an existing board to the current `BoardAssets` and
+/// `NumberPalette`
+#[allow(clippy::type_complexity)]
+pub fn restyle_board(
+    board_assets: Res<BoardAssets>,
+    palette: Res<NumberPalette>,
+    mut sprites: Query<(&BoardSprite, &mut Sprite, &mut Handle<Image>), Without<Tween>>,
+    mut glyphs: Query<
+        (
+            &NumberGlyph,
+            &mut Sprite,
+            &mut Handle<Image>,
+            &mut Visibility,
+        ),
+        Without<BoardSprite>,
+    >,
+    mut texts: Query<(&Parent, &mut Text)>,
+    neighbors: Query<&BombNeighbor>,
+) {
+    for (kind, mut sprite, mut texture) in &mut sprites {
+        let material = match kind {
+            BoardSprite::Background => &board_assets.board_material,
+            BoardSprite::Tile => &board_assets.tile_material,
+            BoardSprite::Cover => &board_assets.covered_tile_material,
+            BoardSprite::Bomb => &board_assets.bomb_material,
+            BoardSprite::Flag => &board_assets.flag_material,
+            BoardSprite::Question => &board_assets.question_material,
+        };
+        sprite.color = material.color;
+        *texture = material.texture.clone();
+    }
+    for (parent, mut text) in &mut texts {
+        let Ok(neighbor) = neighbors.get(parent.get()) else {
+            continue;
+        };
+        for section in text.sections.iter_mut() {
+            section.style.color = palette.color(&board_assets, neighbor.count);
+            section.style.font = board_assets.bomb_counter_font.clone();
+        }
+    }
+    for (glyph, mut sprite, mut texture, mut visibility) in &mut glyphs {
+        sprite.color = palette.color(&board_assets, glyph.count);
+        *texture = board_assets.number_glyph(glyph.count);
+        *visibility = if palette.glyphs {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Starts the game once the board assets are available
+pub fn finish_loading(
+    board_assets: Option<Res<BoardAssets>>,
+    themes: Option<Res<Themes>>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut reported: Local<bool>,
+) {
+    if board_assets.is_some() {
+        next_state.set(AppState::InGame);
+        return;
+    }
+    if let Some(themes) = themes {
+        if !*reported && asset_server.get_load_state(themes.current()) == Some(LoadState::Failed) {
+            log::error!("Failed to load theme {:?}", themes.current().path());
+            *reported = true;
+        }
+    }
+}
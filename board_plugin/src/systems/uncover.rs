@@ -1,68 +1,220 @@
 use crate::button_style::ExitWindowTitle;
-use crate::events::TileTriggerEvent;
-use crate::{AppState, Board, Bomb, BombNeighbor, Coordinate, Uncover};
+use crate::events::{GameOverEvent, MovePlayedEvent, Sound, SoundEvent, TileTriggerEvent};
+use crate::{
+    AnimationOptions, AppState, Board, BoardAssets, BoardPlugin, Coordinate, CoverEffect,
+    LossIndicator, ReplayAction, Tween, TweenEffect,
+};
 use bevy::log;
 use bevy::prelude::*;
 
+/// Reveals a clicked tile, or chords a clicked number
 pub fn left_click_handler(
-    mut commands: Commands,
-    board: Res<Board>,
+    mut board: ResMut<Board>,
     mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+    mut move_played_ewr: EventWriter<MovePlayedEvent>,
 ) {
     for trigger_event in tile_trigger_evr.read() {
-        if let Some(entity) = board.tile_to_uncover(&trigger_event.0) {
-            commands.entity(*entity).insert(Uncover);
+        move_played_ewr.send(MovePlayedEvent(ReplayAction::Reveal(trigger_event.0)));
+        board.make_first_reveal_safe(&trigger_event.0);
+        if board.click(&trigger_event.0) == Some(true) {
+            log::debug!("Chord at {}", trigger_event.0);
+            sound_ewr.send(SoundEvent(Sound::Chord));
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn uncover_tiles(
     mut commands: Commands,
     mut board: ResMut<Board>,
+    board_assets: Res<BoardAssets>,
+    animations: Option<Res<AnimationOptions>>,
     mut exit_window_tile: ResMut<ExitWindowTitle>,
-    children: Query<(Entity, &Parent), With<Uncover>>,
-    parents: Query<(&Coordinate, Option<&Bomb>, Option<&BombNeighbor>)>,
     mut next_state: ResMut<NextState<AppState>>,
+    mut sound_ewr: EventWriter<SoundEvent>,
+    mut game_over_ewr: EventWriter<GameOverEvent>,
 ) {
-    for (entity, parent) in children.iter() {
-        commands.entity(entity).despawn_recursive();
-
-        let (coord, bomb, bomb_counter) = match parents.get(parent.get()) {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!("{}", e);
-                continue;
-            }
-        };
+    if board.uncovering.is_empty() {
+        return;
+    }
+    let animations = animations.filter(|a| a.enabled);
+    for origin in std::mem::take(&mut board.uncovering) {
+        let revealed = board.reveal(&origin);
+        if revealed.is_empty() {
+            log::debug!("Tried to uncover an already uncovered tile");
+            continue;
+        }
+        log::debug!("Uncovered {} tiles from {}", revealed.len(), origin);
 
-        match board.try_uncover_tile(coord) {
-            None => log::debug!("Tried to uncover an already uncovered tile"),
-            Some(e) => log::debug!("Uncovered tile {} (entity: {:?})", coord, e),
+        if let Some(animations) = &animations {
+            spawn_cover_tweens(
+                &mut commands,
+                &board,
+                &board_assets,
+                animations,
+                board.reveal_origin,
+                &revealed,
+            );
         }
 
-        if board.is_completed() {
-            log::info!("Board completed");
-            exit_window_tile.text = "YOU WON!".into();
-            next_state.set(AppState::Out);
+        let bomb = board.tile_map.is_bomb_at(origin);
+        // Only the clicked tile makes a sound, not the whole opening
+        if origin == board.reveal_origin && !bomb {
+            sound_ewr.send(SoundEvent(if board.tile_map.bomb_count_at(origin) == 0 {
+                Sound::Opening
+            } else {
+                Sound::Reveal
+            }));
         }
 
-        if bomb.is_some() {
+        if bomb {
             log::info!("Boom !");
+            sound_ewr.send(SoundEvent(Sound::Explosion));
+            board.exploded = Some(origin);
+            board.tile_changed(origin);
+            if animations.as_ref().is_some_and(|a| a.explosion) {
+                spawn_board_explosion(&mut commands, &board, &board_assets, origin);
+            }
             exit_window_tile.text = "GAME OVER!".into();
+            game_over_ewr.send(GameOverEvent::Lost(origin));
             next_state.set(AppState::Out);
-        } else if bomb_counter.is_none() {
-            // We propagate the uncovering by adding the `Uncover` component to adjacent tiles
-            // which will then be removed next frame
-            for entity in board.adjacent_covered_tiles(*coord) {
-                commands.entity(entity).insert(Uncover);
-            }
-        };
+            // The clicks queued after the end of the game are dropped
+            break;
+        } else if board.is_completed() {
+            log::info!("Board completed");
+            exit_window_tile.text = if board.practice {
+                "PRACTICE WON!".into()
+            } else {
+                "YOU WON!".into()
+            };
+            sound_ewr.send(SoundEvent(Sound::Win));
+            game_over_ewr.send(GameOverEvent::Won);
+            next_state.set(AppState::Out);
+            break;
+        }
+    }
+}
+
+/// Animates the covers of revealed tiles away, over their chunks which no longer draw them.
+/// The covers of the tiles out of view are not animated
+pub(crate) fn spawn_cover_tweens(
+    commands: &mut Commands,
+    board: &Board,
+    board_assets: &BoardAssets,
+    animations: &AnimationOptions,
+    origin: Coordinate,
+    revealed: &[Coordinate],
+) {
+    let size = Some(Vec2::splat(board.tile_size - board.tile_padding));
+    commands.entity(board.entity).with_children(|parent| {
+        for coord in revealed.iter().filter(|c| board.is_spawned(c)) {
+            let distance = Vec2::new(
+                coord.x as f32 - origin.x as f32,
+                coord.y as f32 - origin.y as f32,
+            )
+            .length();
+            let mut cover = BoardPlugin::tile_cover_bundle(board_assets, size);
+            cover.transform.translation = board.tile_center(coord).extend(3.);
+            parent
+                .spawn(cover)
+                .insert(Name::new("Tile Cover"))
+                .insert(cover_tween(animations, distance));
+        }
+    });
+}
+
+/// Removes a cover, delayed by its distance in tiles to the clicked one so that the covers
+/// ripple away
+pub(crate) fn cover_tween(animations: &AnimationOptions, distance: f32) -> Tween {
+    let effect = match animations.cover_effect {
+        CoverEffect::Fade => TweenEffect::Fade,
+        CoverEffect::Scale => TweenEffect::Scale,
+        CoverEffect::Flip => TweenEffect::Flip,
+    };
+    Tween::new(effect, animations.cover_duration)
+        .with_delay(distance * animations.ripple_delay)
+        .despawning()
+}
+
+/// Highlights a mine that was just clicked, with an explosion if animated
+pub(crate) fn spawn_explosion(
+    parent: &mut ChildBuilder,
+    board_assets: &BoardAssets,
+    size: Option<Vec2>,
+    animated: bool,
+) {
+    spawn_exploded_bomb(parent, board_assets, size);
+    if animated {
+        spawn_explosion_effect(parent, board_assets, size, Vec3::new(0., 0., 3.));
     }
 }
 
-pub fn clear_tiles(mut commands: Commands, mut board: ResMut<Board>) {
-    for (_coord, entity) in board.covered_tiles.iter() {
-        commands.entity(*entity).despawn_recursive();
+/// Explodes a mine that was just clicked over its chunk, which highlights it
+pub(crate) fn spawn_board_explosion(
+    commands: &mut Commands,
+    board: &Board,
+    board_assets: &BoardAssets,
+    coord: Coordinate,
+) {
+    if !board.is_spawned(&coord) {
+        return;
     }
-    board.covered_tiles.clear();
+    let size = Some(Vec2::splat(board.tile_size - board.tile_padding));
+    let translation = board.tile_center(&coord).extend(4.);
+    commands.entity(board.entity).with_children(|parent| {
+        spawn_explosion_effect(parent, board_assets, size, translation);
+    });
+}
+
+fn spawn_explosion_effect(
+    parent: &mut ChildBuilder,
+    board_assets: &BoardAssets,
+    size: Option<Vec2>,
+    translation: Vec3,
+) {
+    let material = &board_assets.explosion_material;
+    parent
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                custom_size: size,
+                color: material.color,
+                ..Default::default()
+            },
+            texture: material.texture.clone(),
+            transform: Transform::from_translation(translation),
+            ..Default::default()
+        })
+        .insert(Name::new("Explosion"))
+        .insert(LossIndicator)
+        .insert(Tween::new(TweenEffect::Explosion, 0.5).despawning());
+}
+
+/// Highlights the clicked mine between the tile and its bomb sprite
+pub(crate) fn spawn_exploded_bomb(
+    parent: &mut ChildBuilder,
+    board_assets: &BoardAssets,
+    size: Option<Vec2>,
+) {
+    let material = &board_assets.exploded_bomb_material;
+    parent
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                custom_size: size,
+                color: material.color,
+                ..Default::default()
+            },
+            texture: material.texture.clone(),
+            transform: Transform::from_xyz(0., 0., 0.5),
+            ..Default::default()
+        })
+        .insert(Name::new("Exploded Bomb"))
+        .insert(LossIndicator);
+}
+
+/// Hides the remaining covers when leaving the game, see `ChunkMeshes::chunk_mesh`. They are
+/// kept so that the fatal move can be undone in practice mode.
+pub fn clear_tiles(mut board: ResMut<Board>) {
+    board.covers_hidden = true;
+    board.all_tiles_changed();
 }
@@ -0,0 +1,185 @@
+use crate::{BoardAssets, SpriteMaterial};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// A board and menu look, loaded from a `.theme.ron` file
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct Theme {
+    pub board_assets: BoardAssets,
+    pub buttons: ButtonColors,
+    pub panel: UiColors,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct UiColors {
+    pub background: Color,
+    pub border: Color,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ButtonColors {
+    pub default: UiColors,
+    pub hover: UiColors,
+    pub press: UiColors,
+}
+
+/// The themes the player can pick from, the first one is used on startup
+#[derive(Debug, Resource)]
+pub struct Themes {
+    pub list: Vec<Handle<Theme>>,
+    pub current: usize,
+}
+
+impl Themes {
+    pub fn current(&self) -> &Handle<Theme> {
+        &self.list[self.current]
+    }
+
+    pub fn next(&mut self) {
+        self.current = (self.current + 1) % self.list.len();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SpriteDescriptor {
+    color: String,
+    #[serde(default)]
+    texture: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UiColorsDescriptor {
+    background: String,
+    border: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ButtonsDescriptor {
+    default: UiColorsDescriptor,
+    hover: UiColorsDescriptor,
+    press: UiColorsDescriptor,
+}
+
+/// The on-disk theme format. Colors are hex strings and paths are relative to the asset folder
+#[derive(Debug, Deserialize)]
+struct ThemeDescriptor {
+    name: String,
+    board: SpriteDescriptor,
+    tile: SpriteDescriptor,
+    covered_tile: SpriteDescriptor,
+    font: String,
+    number_colors: Vec<String>,
+    #[serde(default)]
+    number_glyphs: Vec<String>,
+    flag: SpriteDescriptor,
+    question: SpriteDescriptor,
+    bomb: SpriteDescriptor,
+    exploded_bomb: SpriteDescriptor,
+    wrong_flag: SpriteDescriptor,
+    explosion: SpriteDescriptor,
+    buttons: ButtonsDescriptor,
+    panel: UiColorsDescriptor,
+}
+
+#[derive(Debug, Error)]
+pub enum ThemeLoaderError {
+    #[error("Could not read theme: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse theme: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Invalid color {0:?}")]
+    Color(String),
+}
+
+#[derive(Default)]
+pub struct ThemeLoader;
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = ThemeLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Theme, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let descriptor: ThemeDescriptor = ron::de::from_bytes(&bytes)?;
+            descriptor.into_theme(load_context)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+fn parse_color(hex: &str) -> Result<Color, ThemeLoaderError> {
+    Color::hex(hex).map_err(|_| ThemeLoaderError::Color(hex.to_string()))
+}
+
+impl SpriteDescriptor {
+    fn material(&self, load_context: &mut LoadContext) -> Result<SpriteMaterial, ThemeLoaderError> {
+        Ok(SpriteMaterial {
+            color: parse_color(&self.color)?,
+            texture: match &self.texture {
+                Some(path) => load_context.load(path.clone()),
+                None => default(),
+            },
+        })
+    }
+}
+
+impl UiColorsDescriptor {
+    fn colors(&self) -> Result<UiColors, ThemeLoaderError> {
+        Ok(UiColors {
+            background: parse_color(&self.background)?,
+            border: parse_color(&self.border)?,
+        })
+    }
+}
+
+impl ThemeDescriptor {
+    fn into_theme(self, load_context: &mut LoadContext) -> Result<Theme, ThemeLoaderError> {
+        let board_assets = BoardAssets {
+            label: self.name,
+            board_material: self.board.material(load_context)?,
+            tile_material: self.tile.material(load_context)?,
+            covered_tile_material: self.covered_tile.material(load_context)?,
+            bomb_counter_font: load_context.load(self.font),
+            bomb_counter_colors: self
+                .number_colors
+                .iter()
+                .map(|c| parse_color(c))
+                .collect::<Result<_, _>>()?,
+            flag_material: self.flag.material(load_context)?,
+            question_material: self.question.material(load_context)?,
+            bomb_material: self.bomb.material(load_context)?,
+            exploded_bomb_material: self.exploded_bomb.material(load_context)?,
+            wrong_flag_material: self.wrong_flag.material(load_context)?,
+            explosion_material: self.explosion.material(load_context)?,
+            number_glyphs: self
+                .number_glyphs
+                .into_iter()
+                .map(|path| load_context.load(path))
+                .collect(),
+        };
+        Ok(Theme {
+            board_assets,
+            buttons: ButtonColors {
+                default: self.buttons.default.colors()?,
+                hover: self.buttons.hover.colors()?,
+                press: self.buttons.press.colors()?,
+            },
+            panel: self.panel.colors()?,
+        })
+    }
+}
@@ -0,0 +1,82 @@
+//! Plays boards with `HeadlessBoardPlugin`, the way simulations and CI drive the rules
+
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::*;
+use board_plugin::components::Coordinate;
+use board_plugin::events::{GameOverEvent, TileTriggerEvent};
+use board_plugin::resources::{BoardOptions, Layout};
+use board_plugin::HeadlessBoardPlugin;
+
+/// A single mine in the top left corner, the rest of the board opens from the bottom right one
+const LAYOUT: &str = "
+    *..
+    ...
+    ...
+";
+
+fn app(layout: &str) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, HeadlessBoardPlugin))
+        .insert_resource(BoardOptions {
+            layout: Some(layout.parse::<Layout>().expect("valid layout")),
+            ..Default::default()
+        });
+    // Creates the board
+    app.update();
+    app
+}
+
+/// Clicks the tiles one after the other, returning how the game ended
+fn play(app: &mut App, clicks: &[(u16, u16)]) -> Option<GameOverEvent> {
+    let mut game_over_evr = ManualEventReader::<GameOverEvent>::default();
+    for &(x, y) in clicks {
+        app.world.send_event(TileTriggerEvent(Coordinate { x, y }));
+        // The tiles are uncovered on the frame after the click
+        app.update();
+        app.update();
+        let events = app.world.resource::<Events<GameOverEvent>>();
+        if let Some(result) = game_over_evr.read(events).last() {
+            return Some(*result);
+        }
+    }
+    None
+}
+
+#[test]
+fn clearing_the_board_wins() {
+    let mut app = app(LAYOUT);
+    assert_eq!(play(&mut app, &[(2, 0)]), Some(GameOverEvent::Won));
+}
+
+#[test]
+fn revealing_a_mine_loses() {
+    let mut app = app(LAYOUT);
+    let mine = Coordinate { x: 0, y: 2 };
+    assert_eq!(
+        play(&mut app, &[(1, 1), (0, 2)]),
+        Some(GameOverEvent::Lost(mine))
+    );
+}
+
+#[test]
+fn seeded_boards_play_the_same() {
+    let options = BoardOptions {
+        map_size: (9, 9),
+        bomb_count: 10,
+        seed: Some(7),
+        ..Default::default()
+    };
+    let results: Vec<_> = (0..2)
+        .map(|_| {
+            let mut app = App::new();
+            app.add_plugins((MinimalPlugins, HeadlessBoardPlugin))
+                .insert_resource(options.clone());
+            app.update();
+            let clicks: Vec<(u16, u16)> =
+                (0..9).flat_map(|y| (0..9).map(move |x| (x, y))).collect();
+            play(&mut app, &clicks)
+        })
+        .collect();
+    assert!(results[0].is_some());
+    assert_eq!(results[0], results[1]);
+}
@@ -0,0 +1,233 @@
+use bevy::log;
+use board_plugin::bot::BotKind;
+use board_plugin::resources::{
+    BoardOptions, Difficulty, DifficultyBand, Layout, LevelEditor, NetClient, PuzzlePacks, Replay,
+    SpectatorClient, SpectatorHost,
+};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use std::path::PathBuf;
+
+/// Themes shipped in `assets/themes`, the first one is the default
+pub const THEMES: [&str; 4] = ["default", "classic", "dark", "high_contrast"];
+
+/// Mine Sweeper, built with Bevy
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Board size as WIDTHxHEIGHT, e.g. 16x16
+    #[arg(long, value_parser = parse_size::<u16>)]
+    pub size: Option<(u16, u16)>,
+    /// Number of mines
+    #[arg(long)]
+    pub mines: Option<u32>,
+    /// Seed of the mine placement, the same seed always gives the same board
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Board preset: beginner, intermediate or expert. --size and --mines override it
+    #[arg(long)]
+    pub difficulty: Option<Difficulty>,
+    /// Difficulty band of the generated boards: easy, medium, hard or extreme
+    #[arg(long)]
+    pub band: Option<DifficultyBand>,
+    /// Theme to use instead of the saved one
+    #[arg(long, value_parser = THEMES)]
+    pub theme: Option<String>,
+    /// Window size as WIDTHxHEIGHT, e.g. 700x800
+    #[arg(long, value_parser = parse_size::<f32>)]
+    pub window_size: Option<(f32, f32)>,
+    /// Start in fullscreen
+    #[arg(long)]
+    pub fullscreen: bool,
+    /// Mine layout to play, a text grid where `*` is a mine and `.` a safe tile
+    #[arg(long, value_name = "PATH", conflicts_with = "replay")]
+    pub layout: Option<PathBuf>,
+    /// Replay to play back, as written by --record
+    #[arg(long, value_name = "PATH")]
+    pub replay: Option<PathBuf>,
+    /// Writes the moves of each game to this file when it ends
+    #[arg(long, value_name = "PATH")]
+    pub record: Option<PathBuf>,
+    /// Lets a bot play instead of the mouse: rules or probability
+    #[arg(long, conflicts_with = "replay")]
+    pub bot: Option<BotKind>,
+    /// Delay between the moves of the bot, in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 300)]
+    pub bot_delay: u64,
+    /// Plays on a board without bounds generated from --seed, until a mine is hit
+    #[arg(long, conflicts_with_all = ["layout", "replay", "record", "bot"])]
+    pub endless: bool,
+    /// Two players race on identical boards side by side, one with the mouse and one with the
+    /// keyboard
+    #[arg(long, conflicts_with_all = ["endless", "replay", "record", "bot"])]
+    pub race: bool,
+    /// Joins the games of a minesweeper_server at HOST:PORT, which sets the board
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        conflicts_with_all = ["endless", "race", "layout", "replay", "record", "bot"]
+    )]
+    pub connect: Option<String>,
+    /// Lets spectators watch the games from HOST:PORT
+    #[arg(long, value_name = "ADDRESS", conflicts_with_all = ["endless", "race"])]
+    pub spectators: Option<String>,
+    /// Watches the game of a player started with --spectators at HOST:PORT
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        conflicts_with_all = ["endless", "race", "connect", "spectators", "layout", "replay", "record", "bot"]
+    )]
+    pub spectate: Option<String>,
+    /// Shows the mines to the spectator
+    #[arg(long, requires = "spectate")]
+    pub show_mines: bool,
+    /// Folder of puzzle packs offered in the menu, the ones in `assets/puzzles` by default
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["endless", "race", "connect", "spectators", "spectate", "replay", "record", "bot"]
+    )]
+    pub puzzles: Option<PathBuf>,
+}
+
+fn parse_size<T: std::str::FromStr>(s: &str) -> Result<(T, T), String> {
+    let (width, height) = s
+        .split_once('x')
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {s:?}"))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<T>()
+            .map_err(|_| format!("invalid size {s:?}"))
+    };
+    Ok((parse(width)?, parse(height)?))
+}
+
+impl Cli {
+    /// Applies the board arguments, exiting with a usage error if they are invalid
+    pub fn board_options(&self, mut options: BoardOptions) -> BoardOptions {
+        if let Some(difficulty) = self.difficulty {
+            options.map_size = difficulty.map_size();
+            options.bomb_count = difficulty.bomb_count();
+        }
+        if let Some(size) = self.size {
+            options.map_size = size;
+        }
+        if let Some(mines) = self.mines {
+            options.bomb_count = mines;
+        }
+        let (width, height) = options.map_size;
+        if width == 0 || height == 0 {
+            Self::exit(ErrorKind::ValueValidation, "the board can't be empty");
+        }
+        if options.bomb_count >= width as u32 * height as u32 {
+            Self::exit(
+                ErrorKind::ValueValidation,
+                &format!(
+                    "{} mines don't fit on a {width}x{height} board",
+                    options.bomb_count
+                ),
+            );
+        }
+        options.seed = self.seed;
+        options.band = self.band;
+        if let Some(path) = &self.layout {
+            match Layout::read(path) {
+                Ok(layout) => options.layout = Some(layout),
+                Err(e) => Self::exit(ErrorKind::Io, &format!("{}: {e}", path.display())),
+            }
+        }
+        options
+    }
+
+    pub fn replay(&self) -> Option<Replay> {
+        let path = self.replay.as_ref()?;
+        match Replay::read(path) {
+            Ok(replay) => Some(replay),
+            Err(e) => Self::exit(ErrorKind::Io, &format!("{}: {e}", path.display())),
+        }
+    }
+
+    /// Connects to the server, waiting for the first game to start
+    pub fn net_client(&self) -> Option<NetClient> {
+        let address = self.connect.as_ref()?;
+        log::info!("Connecting to {address}, the game starts once every player joined");
+        match NetClient::connect(address.as_str()) {
+            Ok(client) => Some(client),
+            Err(e) => Self::exit(ErrorKind::Io, &format!("{address}: {e}")),
+        }
+    }
+
+    pub fn spectator_host(&self) -> Option<SpectatorHost> {
+        let address = self.spectators.as_ref()?;
+        match SpectatorHost::bind(address.as_str()) {
+            Ok(host) => Some(host),
+            Err(e) => Self::exit(ErrorKind::Io, &format!("{address}: {e}")),
+        }
+    }
+
+    pub fn spectator_client(&self) -> Option<SpectatorClient> {
+        let address = self.spectate.as_ref()?;
+        match SpectatorClient::connect(address.as_str(), self.show_mines) {
+            Ok(client) => Some(client),
+            Err(e) => Self::exit(ErrorKind::Io, &format!("{address}: {e}")),
+        }
+    }
+
+    /// The daily board is offered in the menu when a single board is played by hand
+    pub fn daily(&self) -> bool {
+        !self.endless
+            && !self.race
+            && self.connect.is_none()
+            && self.spectate.is_none()
+            && self.replay.is_none()
+            && self.bot.is_none()
+    }
+
+    /// The puzzle packs, offered when a single board is played by hand without being recorded
+    /// or watched, as they start from revealed tiles. Without a puzzle directory the game still
+    /// starts, only without the puzzles
+    pub fn puzzles(&self) -> Option<PuzzlePacks> {
+        if !self.daily() || self.spectators.is_some() || self.record.is_some() {
+            return None;
+        }
+        let dir = self.puzzles.clone().unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("assets")
+                .join("puzzles")
+        });
+        match PuzzlePacks::read_dir(&dir) {
+            Ok(packs) => Some(packs),
+            Err(e) => {
+                log::warn!("No puzzles in {}: {e}", dir.display());
+                None
+            }
+        }
+    }
+
+    /// The level editor, offered with the puzzles. It starts from the layout being played and
+    /// saves to its file, or to `layout.txt`
+    pub fn level_editor(&self, options: &BoardOptions) -> Option<LevelEditor> {
+        if !self.daily() || self.spectators.is_some() || self.record.is_some() {
+            return None;
+        }
+        let layout = options.layout.clone().unwrap_or_else(|| Layout {
+            width: options.map_size.0,
+            height: options.map_size.1,
+            mines: Vec::new(),
+        });
+        let path = self
+            .layout
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("layout.txt"));
+        Some(LevelEditor::new(path, layout))
+    }
+
+    pub fn theme_index(&self) -> Option<usize> {
+        let theme = self.theme.as_ref()?;
+        THEMES.iter().position(|name| name == theme)
+    }
+
+    fn exit(kind: ErrorKind, message: &str) -> ! {
+        Self::command().error(kind, message).exit()
+    }
+}
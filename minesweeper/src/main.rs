@@ -1,35 +1,118 @@
 use bevy::input::keyboard::KeyboardInput;
 use bevy::log;
 use bevy::prelude::*;
-use bevy::window::{Window, WindowPlugin, WindowResolution};
+use bevy::window::{Window, WindowMode, WindowPlugin, WindowResolution};
 #[cfg(feature = "inspect")]
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
-use board_plugin::resources::{BoardAssets, BoardOptions, ExitWindowTitle, SpriteMaterial};
+use board_plugin::resources::{
+    AnimationOptions, BoardAudio, BoardOptions, BotPlayer, DailyHistory, EndlessOptions,
+    ExitWindowTitle, RaceOptions, ReplayPlayback, ReplayRecorder, Settings,
+};
 use board_plugin::states::AppState;
+use board_plugin::theme::Themes;
 use board_plugin::BoardPlugin;
 
+use clap::Parser;
+use cli::{Cli, THEMES};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
+
+mod cli;
 
 fn main() {
+    let cli = Cli::parse();
+    let (width, height) = cli.window_size.unwrap_or((700., 800.));
+    // Added first so the options read below can log what they skip
     let mut app = App::new();
-
     app.add_plugins(DefaultPlugins.set(WindowPlugin {
         primary_window: Some(Window {
-            resolution: WindowResolution::new(700., 800.),
+            resolution: WindowResolution::new(width, height),
+            mode: if cli.fullscreen {
+                WindowMode::BorderlessFullscreen
+            } else {
+                WindowMode::Windowed
+            },
             title: "Mine Sweeper".to_string(),
             ..default()
         }),
         ..default()
-    }))
-    .init_resource::<ExitWindowTitle>()
-    .insert_resource(ExitWindowTitle {
-        text: "MENU".into(),
-    })
-    .add_systems(Startup, (setup_camera, setup_board))
-    .add_plugins(BoardPlugin)
-    .add_systems(Update, escape_handler.run_if(in_state(AppState::InGame)));
+    }));
+
+    let mut settings = Settings::load();
+    if let Some(theme) = cli.theme_index() {
+        settings.theme = theme;
+    }
+    // Tile size, padding, safe start and question marks come from the saved `Settings`
+    let mut board_options = cli.board_options(BoardOptions {
+        map_size: (20, 20),
+        bomb_count: 40,
+        practice_mode: true,
+        ..Default::default()
+    });
+    let replay = cli.replay();
+    if let Some(replay) = &replay {
+        board_options.layout = Some(replay.layout.clone());
+    }
+    let net_client = cli.net_client();
+    if let Some(client) = &net_client {
+        board_options = client.board_options(board_options);
+    }
+    let spectator_host = cli.spectator_host();
+    let spectator_client = cli.spectator_client();
+    let puzzles = cli.puzzles();
+    let level_editor = cli.level_editor(&board_options);
+
+    app.init_resource::<ExitWindowTitle>()
+        .init_resource::<AnimationOptions>()
+        .insert_resource(settings)
+        .insert_resource(board_options)
+        .insert_resource(ExitWindowTitle {
+            text: "MENU".into(),
+        })
+        .add_systems(Startup, (setup_camera, setup_board))
+        .add_plugins(BoardPlugin)
+        .add_systems(Update, escape_handler.run_if(in_state(AppState::InGame)));
+
+    if cli.daily() {
+        app.insert_resource(DailyHistory::load());
+    }
+    if let Some(puzzles) = puzzles {
+        app.insert_resource(puzzles);
+    }
+    if let Some(editor) = level_editor {
+        app.insert_resource(editor);
+    }
+    if let Some(replay) = replay {
+        app.insert_resource(ReplayPlayback::new(replay));
+    }
+    if let Some(path) = cli.record {
+        app.insert_resource(ReplayRecorder::new(path));
+    }
+    if let Some(bot) = cli.bot {
+        // The bot guesses the same way for a given seed
+        let player = bot.player(cli.seed.unwrap_or_default());
+        app.insert_resource(BotPlayer::new(player, Duration::from_millis(cli.bot_delay)));
+    }
+    if cli.endless {
+        app.insert_resource(EndlessOptions {
+            seed: cli.seed,
+            ..Default::default()
+        });
+    }
+    if cli.race {
+        app.insert_resource(RaceOptions);
+    }
+    if let Some(client) = net_client {
+        app.insert_resource(client);
+    }
+    if let Some(host) = spectator_host {
+        app.insert_resource(host);
+    }
+    if let Some(client) = spectator_client {
+        app.insert_resource(client);
+    }
 
     #[cfg(feature = "inspect")]
     app.add_plugins(WorldInspectorPlugin::new());
@@ -42,39 +125,27 @@ fn setup_camera(mut commands: Commands) {
 }
 
 fn setup_board(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(BoardOptions {
-        map_size: (20, 20),
-        bomb_count: 40,
-        tile_padding: 1.,
-        safe_start: true,
-        ..Default::default()
-    });
     let cur_path = PathBuf::from_str(env!("CARGO_MANIFEST_DIR")).unwrap();
     let asset_path = cur_path.join("assets");
-    commands.insert_resource(BoardAssets {
-        label: "Default".to_string(),
-        board_material: SpriteMaterial {
-            color: Color::WHITE,
-            ..Default::default()
-        },
-        tile_material: SpriteMaterial {
-            color: Color::GRAY,
-            ..Default::default()
-        },
-        covered_tile_material: SpriteMaterial {
-            color: Color::DARK_GRAY,
-            ..Default::default()
-        },
-        bomb_counter_font: asset_server.load(asset_path.join("fonts").join("pixeled.ttf")),
-        bomb_counter_colors: BoardAssets::default_colors(),
-        flag_material: SpriteMaterial {
-            texture: asset_server.load(asset_path.join("sprites").join("flag.png")),
-            color: Color::WHITE,
-        },
-        bomb_material: SpriteMaterial {
-            texture: asset_server.load(asset_path.join("sprites").join("bomb.png")),
-            color: Color::WHITE,
-        },
+    // Themes are looked up relative to the asset folder so they can be hot-reloaded
+    commands.insert_resource(Themes {
+        list: THEMES
+            .iter()
+            .map(|name| asset_server.load(format!("themes/{name}.theme.ron")))
+            .collect(),
+        current: 0,
+    });
+    let sound_path = asset_path.join("sounds");
+    commands.insert_resource(BoardAudio {
+        reveal: asset_server.load(sound_path.join("reveal.wav")),
+        opening: asset_server.load(sound_path.join("opening.wav")),
+        flag: asset_server.load(sound_path.join("flag.wav")),
+        unflag: asset_server.load(sound_path.join("unflag.wav")),
+        chord: asset_server.load(sound_path.join("chord.wav")),
+        explosion: asset_server.load(sound_path.join("explosion.wav")),
+        win: asset_server.load(sound_path.join("win.wav")),
+        button_hover: asset_server.load(sound_path.join("button_hover.wav")),
+        button_press: asset_server.load(sound_path.join("button_press.wav")),
     });
     log::info!("Loaded assets!");
 }
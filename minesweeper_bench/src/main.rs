@@ -0,0 +1,167 @@
+use board_plugin::bot::rating::{BoardRating, Deduction};
+use board_plugin::bot::{harness, BotKind};
+use board_plugin::game::Game;
+use board_plugin::resources::{BoardOptions, Difficulty, DifficultyBand};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use std::time::Instant;
+
+/// Measures the Mine Sweeper bots
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Plays games with the bots and reports their win rate, time and guesses
+    Play {
+        /// Bot to play with: rules or probability. All of them by default
+        #[arg(long)]
+        bot: Option<BotKind>,
+        /// Number of games per bot
+        #[arg(long, default_value_t = 1000)]
+        games: u32,
+        #[command(flatten)]
+        board: BoardArgs,
+    },
+    /// Generates boards and rates how hard they are to solve
+    Rate {
+        /// Number of boards
+        #[arg(long, default_value_t = 1000)]
+        boards: u32,
+        /// Prints the rating of every board
+        #[arg(long)]
+        list: bool,
+        #[command(flatten)]
+        board: BoardArgs,
+    },
+}
+
+#[derive(Debug, clap::Args)]
+struct BoardArgs {
+    /// Board preset: beginner, intermediate or expert. --size and --mines override it
+    #[arg(long, default_value = "beginner")]
+    difficulty: Difficulty,
+    /// Board size as WIDTHxHEIGHT, e.g. 16x16
+    #[arg(long, value_parser = parse_size)]
+    size: Option<(u16, u16)>,
+    /// Number of mines
+    #[arg(long)]
+    mines: Option<u32>,
+    /// Seed of the first board, the next ones use the following seeds
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Only generate boards rated in this band: easy, medium, hard or extreme
+    #[arg(long)]
+    band: Option<DifficultyBand>,
+}
+
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let (width, height) = s
+        .split_once('x')
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {s:?}"))?;
+    let parse = |v: &str| v.trim().parse().map_err(|_| format!("invalid size {s:?}"));
+    Ok((parse(width)?, parse(height)?))
+}
+
+impl BoardArgs {
+    /// Exits with a usage error if the board is invalid
+    fn options(&self) -> BoardOptions {
+        self.board_options()
+            .unwrap_or_else(|e| Cli::command().error(ErrorKind::ValueValidation, e).exit())
+    }
+
+    fn board_options(&self) -> Result<BoardOptions, String> {
+        let (width, height) = self.size.unwrap_or(self.difficulty.map_size());
+        let bomb_count = self.mines.unwrap_or(self.difficulty.bomb_count());
+        if width == 0 || height == 0 {
+            return Err("the board can't be empty".to_string());
+        }
+        if bomb_count >= width as u32 * height as u32 {
+            return Err(format!(
+                "{bomb_count} mines don't fit on a {width}x{height} board"
+            ));
+        }
+        Ok(BoardOptions {
+            map_size: (width, height),
+            bomb_count,
+            band: self.band,
+            ..Default::default()
+        })
+    }
+}
+
+fn band(score: f32) -> DifficultyBand {
+    DifficultyBand::ALL
+        .into_iter()
+        .find(|band| band.distance(score) == 0.)
+        .unwrap_or(DifficultyBand::Extreme)
+}
+
+fn rate(options: &BoardOptions, boards: u32, seed: u64, list: bool) {
+    let start = Instant::now();
+    let ratings: Vec<BoardRating> = (0..boards)
+        .map(|i| {
+            let options = BoardOptions {
+                seed: Some(seed.wrapping_add(i as u64)),
+                ..options.clone()
+            };
+            let rating = Game::new(&options).rating();
+            if list {
+                println!("seed {}: {rating}", options.seed.unwrap_or_default());
+            }
+            rating
+        })
+        .collect();
+    let elapsed = start.elapsed();
+    let count = ratings.len().max(1) as f32;
+    let average = |value: fn(&BoardRating) -> f32| ratings.iter().map(value).sum::<f32>() / count;
+    println!(
+        "{boards} boards in {elapsed:.2?}: 3BV {:.1}, {:.1} openings, {:.2} guesses, score {:.2} on average",
+        average(|r| r.bbbv as f32),
+        average(|r| r.openings as f32),
+        average(|r| r.forced_guesses as f32),
+        average(|r| r.score),
+    );
+    let no_guess = ratings.iter().filter(|r| r.forced_guesses == 0).count();
+    println!("without guessing: {:.1}%", no_guess as f32 * 100. / count);
+    for depth in [Deduction::SingleTile, Deduction::Subset, Deduction::Global] {
+        let boards = ratings.iter().filter(|r| r.depth == depth).count();
+        println!("{depth:?} deductions: {:.1}%", boards as f32 * 100. / count);
+    }
+    for band in DifficultyBand::ALL {
+        let boards = ratings
+            .iter()
+            .filter(|r| self::band(r.score) == band)
+            .count();
+        println!("{band}: {:.1}%", boards as f32 * 100. / count);
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Play { bot, games, board } => {
+            let options = board.options();
+            let bots = match bot {
+                Some(bot) => vec![bot],
+                None => BotKind::ALL.to_vec(),
+            };
+            for bot in bots {
+                let mut player = bot.player(board.seed);
+                println!(
+                    "{}",
+                    harness::run(player.as_mut(), &options, games, board.seed)
+                );
+            }
+        }
+        Command::Rate {
+            boards,
+            list,
+            board,
+        } => rate(&board.options(), boards, board.seed, list),
+    }
+}
@@ -0,0 +1,237 @@
+//! Hosts networked Mine Sweeper games: the rules are applied to the moves of the players, who
+//! are sent the accepted ones, see `board_plugin::net`
+
+use board_plugin::game::{Game, GameState};
+use board_plugin::net::{
+    self, ClientMessage, MessageReader, NetAction, NetError, NetGame, NetMode, ServerMessage,
+};
+use board_plugin::resources::BoardOptions;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+/// A message of a player, `None` once they left
+type Incoming = (usize, Option<ClientMessage>);
+
+/// Forwards the messages of a player to the game loop until they leave
+fn listen(player: usize, stream: TcpStream, sender: Sender<Incoming>) {
+    let mut reader = MessageReader::new(stream);
+    loop {
+        match reader.read() {
+            Ok(Some(message)) => {
+                if sender.send((player, Some(message))).is_err() {
+                    return;
+                }
+            }
+            Ok(None) => break,
+            Err(NetError::Parse(e)) => eprintln!("player {}: {e}", player + 1),
+            Err(e) => {
+                eprintln!("player {}: {e}", player + 1);
+                break;
+            }
+        }
+    }
+    let _ = sender.send((player, None));
+}
+
+/// Applies the rules to the moves of the players and sends them the accepted ones
+struct Server {
+    mode: NetMode,
+    options: BoardOptions,
+    /// `None` once the player left
+    streams: Vec<Option<TcpStream>>,
+    /// One game per player in versus, a single shared one in co-op
+    games: Vec<Game>,
+    /// Players who didn't hit a mine or leave
+    playing: Vec<bool>,
+    over: bool,
+}
+
+impl Server {
+    fn start(&mut self) {
+        let layout = Game::new(&self.options).layout();
+        // The next games are random
+        self.options.seed = None;
+        let options = BoardOptions {
+            layout: Some(layout.clone()),
+            ..self.options.clone()
+        };
+        let boards = match self.mode {
+            NetMode::Versus => self.streams.len(),
+            NetMode::Coop => 1,
+        };
+        self.games = (0..boards).map(|_| Game::new(&options)).collect();
+        self.playing = self.streams.iter().map(Option::is_some).collect();
+        self.over = false;
+        println!(
+            "{} game started on a {}x{} board with {} mines",
+            self.mode,
+            layout.width,
+            layout.height,
+            layout.mines.len()
+        );
+        let players = self.streams.len();
+        for (player, stream) in self.streams.iter_mut().enumerate() {
+            let Some(stream) = stream else {
+                continue;
+            };
+            let message = ServerMessage::Start(NetGame {
+                player,
+                players,
+                mode: self.mode,
+                layout: layout.clone(),
+            });
+            if let Err(e) = net::send(stream, &message) {
+                eprintln!("player {}: {e}", player + 1);
+            }
+        }
+    }
+
+    fn broadcast(&mut self, message: &ServerMessage) {
+        for (player, stream) in self.streams.iter_mut().enumerate() {
+            let Some(stream) = stream else {
+                continue;
+            };
+            if let Err(e) = net::send(stream, message) {
+                eprintln!("player {}: {e}", player + 1);
+            }
+        }
+    }
+
+    fn handle(&mut self, player: usize, message: Option<ClientMessage>) {
+        match message {
+            None => {
+                println!("player {} left", player + 1);
+                self.streams[player] = None;
+                self.playing[player] = false;
+                self.check_winner();
+            }
+            Some(ClientMessage::NewGame) => {
+                if self.over {
+                    self.start();
+                }
+            }
+            Some(ClientMessage::Action(action)) => self.play(player, action),
+        }
+    }
+
+    fn play(&mut self, player: usize, action: NetAction) {
+        if self.over || !self.playing[player] {
+            return;
+        }
+        let game = match self.mode {
+            NetMode::Versus => &mut self.games[player],
+            NetMode::Coop => &mut self.games[0],
+        };
+        match action {
+            NetAction::Reveal(coord) => {
+                // Clicking a revealed number chords it, the clients do the same
+                let revealed = if game.is_covered(coord) {
+                    game.reveal(coord)
+                } else {
+                    game.chord(coord)
+                };
+                if revealed.is_empty() {
+                    return;
+                }
+                let safe_tiles = game.width() as u32 * game.height() as u32 - game.bomb_count();
+                let progress = (game.revealed_count() * 100 / safe_tiles.max(1)).min(100) as u8;
+                let state = game.state();
+                self.broadcast(&ServerMessage::Revealed { player, coord });
+                self.broadcast(&ServerMessage::Progress { player, progress });
+                match (state, self.mode) {
+                    (GameState::Playing, _) => (),
+                    (GameState::Won, _) => self.finish(Some(player)),
+                    (GameState::Lost(_), NetMode::Coop) => self.finish(None),
+                    (GameState::Lost(_), NetMode::Versus) => {
+                        println!("player {} hit a mine", player + 1);
+                        self.playing[player] = false;
+                        self.check_winner();
+                    }
+                }
+            }
+            NetAction::Mark(coord) => {
+                if let Some(mark) = game.toggle_mark(coord) {
+                    self.broadcast(&ServerMessage::Marked {
+                        player,
+                        coord,
+                        mark,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Ends the game once nobody is playing, or a single player is left in versus
+    fn check_winner(&mut self) {
+        if self.over {
+            return;
+        }
+        let playing: Vec<usize> = (0..self.playing.len())
+            .filter(|player| self.playing[*player])
+            .collect();
+        match playing.as_slice() {
+            [] => self.finish(None),
+            [winner] if self.mode == NetMode::Versus && self.playing.len() > 1 => {
+                self.finish(Some(*winner))
+            }
+            _ => (),
+        }
+    }
+
+    fn finish(&mut self, winner: Option<usize>) {
+        match winner {
+            Some(player) => println!("game over, player {} won", player + 1),
+            None => println!("game over"),
+        }
+        self.over = true;
+        self.broadcast(&ServerMessage::GameOver { winner });
+    }
+}
+
+/// Waits for the players, then plays games with them until every player left
+pub fn serve(listener: TcpListener, players: usize, mode: NetMode, options: BoardOptions) {
+    let (sender, receiver) = mpsc::channel();
+    let mut streams = Vec::new();
+    while streams.len() < players {
+        let (stream, address) = match listener.accept() {
+            Ok(connection) => connection,
+            Err(e) => {
+                eprintln!("{e}");
+                continue;
+            }
+        };
+        let player = streams.len();
+        let reader = match stream.try_clone() {
+            Ok(reader) => reader,
+            Err(e) => {
+                eprintln!("{address}: {e}");
+                continue;
+            }
+        };
+        println!("player {} joined from {address}", player + 1);
+        let sender = sender.clone();
+        thread::spawn(move || listen(player, reader, sender));
+        streams.push(Some(stream));
+    }
+    // Later connections are refused
+    drop(listener);
+    drop(sender);
+
+    let mut server = Server {
+        mode,
+        options,
+        streams,
+        games: Vec::new(),
+        playing: Vec::new(),
+        over: false,
+    };
+    server.start();
+    for (player, message) in receiver {
+        server.handle(player, message);
+        if server.streams.iter().all(Option::is_none) {
+            println!("every player left");
+            break;
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use board_plugin::net::{NetMode, DEFAULT_PORT};
+use board_plugin::resources::{BoardOptions, Difficulty, DifficultyBand};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use std::net::TcpListener;
+
+/// Hosts networked Mine Sweeper games, joined with `minesweeper --connect`
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    #[arg(long, default_value_t = DEFAULT_PORT)]
+    port: u16,
+    /// Number of players, the first game starts once all of them joined
+    #[arg(long, default_value_t = 2)]
+    players: usize,
+    /// versus: the players race on copies of the same board, coop: they share a single board
+    #[arg(long, default_value = "versus")]
+    mode: NetMode,
+    /// Board preset: beginner, intermediate or expert. --size and --mines override it
+    #[arg(long, default_value = "beginner")]
+    difficulty: Difficulty,
+    /// Board size as WIDTHxHEIGHT, e.g. 16x16
+    #[arg(long, value_parser = parse_size)]
+    size: Option<(u16, u16)>,
+    /// Number of mines
+    #[arg(long)]
+    mines: Option<u32>,
+    /// Seed of the first board, the next ones are random
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Only generate boards rated in this band: easy, medium, hard or extreme
+    #[arg(long)]
+    band: Option<DifficultyBand>,
+    /// Cycle marks through question marks
+    #[arg(long)]
+    question_marks: bool,
+}
+
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let (width, height) = s
+        .split_once('x')
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {s:?}"))?;
+    let parse = |v: &str| v.trim().parse().map_err(|_| format!("invalid size {s:?}"));
+    Ok((parse(width)?, parse(height)?))
+}
+
+impl Cli {
+    /// Exits with a usage error if the board is invalid
+    fn options(&self) -> BoardOptions {
+        let (width, height) = self.size.unwrap_or(self.difficulty.map_size());
+        let bomb_count = self.mines.unwrap_or(self.difficulty.bomb_count());
+        if width == 0 || height == 0 {
+            Self::exit(ErrorKind::ValueValidation, "the board can't be empty");
+        }
+        if bomb_count >= width as u32 * height as u32 {
+            Self::exit(
+                ErrorKind::ValueValidation,
+                &format!("{bomb_count} mines don't fit on a {width}x{height} board"),
+            );
+        }
+        if self.players == 0 {
+            Self::exit(ErrorKind::ValueValidation, "at least one player is needed");
+        }
+        BoardOptions {
+            map_size: (width, height),
+            bomb_count,
+            seed: self.seed,
+            band: self.band,
+            question_marks: self.question_marks,
+            ..Default::default()
+        }
+    }
+
+    fn exit(kind: ErrorKind, message: &str) -> ! {
+        Self::command().error(kind, message).exit()
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let options = cli.options();
+    let listener = TcpListener::bind((cli.host.as_str(), cli.port)).unwrap_or_else(|e| {
+        Cli::exit(
+            ErrorKind::Io,
+            &format!("could not listen on {}:{}: {e}", cli.host, cli.port),
+        )
+    });
+    println!(
+        "listening on {}:{}, waiting for {} players",
+        cli.host, cli.port, cli.players
+    );
+    minesweeper_server::serve(listener, cli.players, cli.mode, options);
+}
@@ -0,0 +1,129 @@
+//! Plays games with two clients connected to a server on localhost
+
+use board_plugin::components::Coordinate;
+use board_plugin::net::{self, ClientMessage, MessageReader, NetAction, NetMode, ServerMessage};
+use board_plugin::resources::{BoardOptions, Layout};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// A single mine in the top left corner, the rest of the board opens from the bottom right one
+const LAYOUT: &str = "
+    *..
+    ...
+    ...
+";
+
+struct Client {
+    player: usize,
+    stream: TcpStream,
+    reader: MessageReader<TcpStream>,
+}
+
+impl Client {
+    /// Joins the server, `start` has to be called once every player joined
+    fn connect(address: SocketAddr) -> Self {
+        let stream = TcpStream::connect(address).expect("server accepts players");
+        // Fails the test instead of waiting forever for a message that never comes
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        Self {
+            player: 0,
+            reader: MessageReader::new(stream.try_clone().unwrap()),
+            stream,
+        }
+    }
+
+    fn start(&mut self, mode: NetMode) {
+        let Some(ServerMessage::Start(game)) = self.receive() else {
+            panic!("the game should start once every player joined");
+        };
+        assert_eq!(game.mode, mode);
+        assert_eq!(game.players, 2);
+        assert_eq!(game.layout.mines, vec![Coordinate { x: 0, y: 2 }]);
+        self.player = game.player;
+    }
+
+    fn reveal(&mut self, x: u16, y: u16) {
+        let action = ClientMessage::Action(NetAction::Reveal(Coordinate { x, y }));
+        net::send(&mut self.stream, &action).unwrap();
+    }
+
+    fn receive(&mut self) -> Option<ServerMessage> {
+        self.reader.read().unwrap()
+    }
+
+    /// Reads the messages sent for a reveal of `player`: the tile, then the progress returned
+    fn expect_revealed(&mut self, player: usize, coord: Coordinate) -> u8 {
+        assert!(matches!(
+            self.receive(),
+            Some(ServerMessage::Revealed { player: p, coord: c }) if p == player && c == coord
+        ));
+        match self.receive() {
+            Some(ServerMessage::Progress {
+                player: p,
+                progress,
+            }) if p == player => progress,
+            message => panic!("expected the progress of player {player}, got {message:?}"),
+        }
+    }
+
+    fn expect_game_over(&mut self, winner: Option<usize>) {
+        assert!(matches!(
+            self.receive(),
+            Some(ServerMessage::GameOver { winner: w }) if w == winner
+        ));
+    }
+}
+
+/// Runs the server on its own thread, returning it with the two players sorted by index
+fn start(mode: NetMode) -> (JoinHandle<()>, [Client; 2]) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let options = BoardOptions {
+        layout: Some(LAYOUT.parse::<Layout>().unwrap()),
+        ..Default::default()
+    };
+    let server = thread::spawn(move || minesweeper_server::serve(listener, 2, mode, options));
+    let mut clients = [Client::connect(address), Client::connect(address)];
+    for client in &mut clients {
+        client.start(mode);
+    }
+    clients.sort_by_key(|client| client.player);
+    assert_eq!([clients[0].player, clients[1].player], [0, 1]);
+    (server, clients)
+}
+
+#[test]
+fn versus_game_is_won_by_the_first_to_clear_their_board() {
+    let (server, [mut first, mut second]) = start(NetMode::Versus);
+    // The opening clears the board of the first player only
+    let opening = Coordinate { x: 2, y: 0 };
+    first.reveal(opening.x, opening.y);
+    for client in [&mut first, &mut second] {
+        assert_eq!(client.expect_revealed(0, opening), 100);
+        client.expect_game_over(Some(0));
+    }
+    drop((first, second));
+    server.join().unwrap();
+}
+
+#[test]
+fn coop_game_is_lost_by_either_player() {
+    let (server, [mut first, mut second]) = start(NetMode::Coop);
+    let number = Coordinate { x: 1, y: 1 };
+    first.reveal(number.x, number.y);
+    for client in [&mut first, &mut second] {
+        // One of the 8 safe tiles
+        assert_eq!(client.expect_revealed(0, number), 12);
+    }
+    let mine = Coordinate { x: 0, y: 2 };
+    second.reveal(mine.x, mine.y);
+    for client in [&mut first, &mut second] {
+        client.expect_revealed(1, mine);
+        client.expect_game_over(None);
+    }
+    drop((first, second));
+    server.join().unwrap();
+}
@@ -0,0 +1,263 @@
+use board_plugin::components::Coordinate;
+use board_plugin::game::{Game, GameState, TileView};
+use board_plugin::resources::{BoardOptions, Difficulty, DifficultyBand, Layout, Mark};
+use clap::Parser;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{cursor, execute, queue};
+use std::io::{self, Stdout, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Mine Sweeper in the terminal
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Board preset: beginner, intermediate or expert. --size and --mines override it
+    #[arg(long, default_value = "beginner")]
+    difficulty: Difficulty,
+    /// Board size as WIDTHxHEIGHT, e.g. 16x16
+    #[arg(long, value_parser = parse_size)]
+    size: Option<(u16, u16)>,
+    /// Number of mines
+    #[arg(long)]
+    mines: Option<u32>,
+    /// Seed of the mine placement
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Difficulty band of the generated boards: easy, medium, hard or extreme
+    #[arg(long)]
+    band: Option<DifficultyBand>,
+    /// Mine layout to play, a text grid where `*` is a mine and `.` a safe tile
+    #[arg(long, value_name = "PATH")]
+    layout: Option<PathBuf>,
+}
+
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let (width, height) = s
+        .split_once('x')
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {s:?}"))?;
+    let parse = |v: &str| v.trim().parse().map_err(|_| format!("invalid size {s:?}"));
+    Ok((parse(width)?, parse(height)?))
+}
+
+impl Cli {
+    fn board_options(&self) -> io::Result<BoardOptions> {
+        let layout = match &self.layout {
+            Some(path) => Some(
+                Layout::read(path).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            ),
+            None => None,
+        };
+        let map_size = self.size.unwrap_or(self.difficulty.map_size());
+        if map_size.0 == 0 || map_size.1 == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the board can't be empty",
+            ));
+        }
+        Ok(BoardOptions {
+            map_size,
+            bomb_count: self.mines.unwrap_or(self.difficulty.bomb_count()),
+            seed: self.seed,
+            band: self.band,
+            layout,
+            question_marks: true,
+            ..Default::default()
+        })
+    }
+}
+
+const HELP: &str = "arrows/hjkl move  space reveal/chord  f flag  c chord  n new game  q quit";
+
+struct App {
+    options: BoardOptions,
+    game: Game,
+    cursor: Coordinate,
+    started: Option<Instant>,
+    finished: Option<Duration>,
+}
+
+impl App {
+    fn new(options: BoardOptions) -> Self {
+        let game = Game::new(&options);
+        let cursor = Coordinate {
+            x: game.width() / 2,
+            y: game.height() / 2,
+        };
+        Self {
+            options,
+            game,
+            cursor,
+            started: None,
+            finished: None,
+        }
+    }
+
+    fn restart(&mut self) {
+        *self = Self::new(self.options.clone());
+    }
+
+    fn elapsed(&self) -> Duration {
+        match (self.finished, self.started) {
+            (Some(finished), _) => finished,
+            (None, Some(started)) => started.elapsed(),
+            (None, None) => Duration::ZERO,
+        }
+    }
+
+    fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let x = (self.cursor.x as i32 + dx).clamp(0, self.game.width() as i32 - 1);
+        let y = (self.cursor.y as i32 + dy).clamp(0, self.game.height() as i32 - 1);
+        self.cursor = Coordinate {
+            x: x as u16,
+            y: y as u16,
+        };
+    }
+
+    /// Reveals the cursor tile, or chords it if it is already revealed
+    fn reveal(&mut self) {
+        self.started.get_or_insert_with(Instant::now);
+        if self.game.is_covered(self.cursor) {
+            self.game.reveal(self.cursor);
+        } else {
+            self.game.chord(self.cursor);
+        }
+        if self.game.state() != GameState::Playing && self.finished.is_none() {
+            self.finished = Some(self.elapsed());
+        }
+    }
+
+    /// Returns false once the player quits
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(0, 1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(0, -1),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(1, 0),
+            KeyCode::Char(' ') | KeyCode::Enter | KeyCode::Char('c') => self.reveal(),
+            KeyCode::Char('f') => {
+                self.game.toggle_mark(self.cursor);
+            }
+            KeyCode::Char('n') => self.restart(),
+            _ => (),
+        }
+        true
+    }
+
+    fn tile_glyph(&self, coord: Coordinate) -> (String, Color) {
+        let lost = matches!(self.game.state(), GameState::Lost(_));
+        match self.game.tile(coord) {
+            TileView::Covered(Some(Mark::Flag)) => {
+                if lost && self.game.is_mine(coord) == Some(false) {
+                    ("X".into(), Color::Red)
+                } else {
+                    ("F".into(), Color::Yellow)
+                }
+            }
+            TileView::Covered(_) if lost && self.game.is_mine(coord) == Some(true) => {
+                ("*".into(), Color::White)
+            }
+            TileView::Covered(Some(Mark::Question)) => ("?".into(), Color::Yellow),
+            TileView::Covered(None) => ("#".into(), Color::DarkGrey),
+            TileView::Mine => ("*".into(), Color::Red),
+            TileView::Revealed(0) => (" ".into(), Color::Reset),
+            TileView::Revealed(count) => {
+                let color = match count {
+                    1 => Color::Blue,
+                    2 => Color::Green,
+                    3 => Color::Red,
+                    4 => Color::DarkBlue,
+                    5 => Color::DarkRed,
+                    6 => Color::Cyan,
+                    7 => Color::Magenta,
+                    _ => Color::Grey,
+                };
+                (count.to_string(), color)
+            }
+        }
+    }
+
+    fn draw(&self, out: &mut Stdout) -> io::Result<()> {
+        queue!(out, cursor::MoveTo(0, 0))?;
+        let status = match self.game.state() {
+            GameState::Playing => "",
+            GameState::Won => "  YOU WON!",
+            GameState::Lost(_) => "  GAME OVER!",
+        };
+        queue!(
+            out,
+            Print(format!(
+                "Mines: {:>3}  Time: {:>4}{}",
+                self.game.mines_left(),
+                self.elapsed().as_secs(),
+                status
+            )),
+            Clear(ClearType::UntilNewLine),
+            cursor::MoveToNextLine(1),
+        )?;
+        // The first row is the top of the board, as in the window
+        for y in (0..self.game.height()).rev() {
+            for x in 0..self.game.width() {
+                let coord = Coordinate { x, y };
+                let (glyph, color) = self.tile_glyph(coord);
+                if coord == self.cursor {
+                    queue!(out, SetAttribute(Attribute::Reverse))?;
+                }
+                queue!(
+                    out,
+                    SetForegroundColor(color),
+                    Print(format!("{glyph} ")),
+                    ResetColor,
+                    SetAttribute(Attribute::Reset)
+                )?;
+            }
+            queue!(
+                out,
+                Clear(ClearType::UntilNewLine),
+                cursor::MoveToNextLine(1)
+            )?;
+        }
+        queue!(out, Print(HELP), Clear(ClearType::FromCursorDown))?;
+        out.flush()
+    }
+}
+
+/// Restores the terminal when dropped, including on panics
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new(out: &mut Stdout) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+    let mut app = App::new(cli.board_options()?);
+    let mut out = io::stdout();
+    let _guard = TerminalGuard::new(&mut out)?;
+    loop {
+        app.draw(&mut out)?;
+        // Wake up regularly to update the timer
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key) {
+                return Ok(());
+            }
+        }
+    }
+}